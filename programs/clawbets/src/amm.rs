@@ -0,0 +1,149 @@
+use anchor_lang::prelude::*;
+use crate::errors::ClawBetsError;
+use crate::payout::{bps_of, Payout};
+
+/// Buy `position` shares (`true` = YES) from a constant-product pool for `amount`
+/// of collateral. The collateral mints `amount` YES and `amount` NO shares into the
+/// pool, which then pays out shares of the bought side until `yes * no` is back to
+/// its value before the trade. Rounding favours the pool.
+///
+/// Returns the new `(yes_reserve, no_reserve)` and the shares bought.
+pub fn buy(yes_reserve: u64, no_reserve: u64, position: bool, amount: u64) -> Result<(u64, u64, u64)> {
+    require!(yes_reserve > 0 && no_reserve > 0, ClawBetsError::InvalidAmmPool);
+    let (bought, other) = if position {
+        (yes_reserve, no_reserve)
+    } else {
+        (no_reserve, yes_reserve)
+    };
+
+    let k = (bought as u128)
+        .checked_mul(other as u128)
+        .ok_or(ClawBetsError::Overflow)?;
+    let other_after = other.checked_add(amount).ok_or(ClawBetsError::Overflow)?;
+    // Rounding the bought-side reserve up keeps the invariant from shrinking
+    let bought_after = k.div_ceil(other_after as u128);
+    let bought_after: u64 = u64::try_from(bought_after).map_err(|_| ClawBetsError::Overflow)?;
+    let shares = bought
+        .checked_add(amount)
+        .ok_or(ClawBetsError::Overflow)?
+        .checked_sub(bought_after)
+        .ok_or(ClawBetsError::Overflow)?;
+
+    Ok(if position {
+        (bought_after, other_after, shares)
+    } else {
+        (other_after, bought_after, shares)
+    })
+}
+
+/// Price of one `position` share in basis points of a unit of collateral, which is
+/// also the pool's implied probability of that outcome.
+pub fn price_bps(yes_reserve: u64, no_reserve: u64, position: bool) -> Result<u16> {
+    let total = (yes_reserve as u128) + (no_reserve as u128);
+    require!(total > 0, ClawBetsError::InvalidAmmPool);
+    let other = if position { no_reserve } else { yes_reserve };
+    Ok(((other as u128) * 10000 / total) as u16)
+}
+
+/// Redemption of `shares` winning shares, one unit of collateral each, bought for
+/// `cost`. Fees are taken from the profit over the cost only.
+pub fn redemption(
+    shares: u64,
+    cost: u64,
+    protocol_fee_bps: u16,
+    creator_fee_bps: u16,
+) -> Result<Payout> {
+    if shares <= cost {
+        return Ok(Payout {
+            stake: shares,
+            share: 0,
+            protocol_fee: 0,
+            creator_fee: 0,
+        });
+    }
+
+    let share = shares - cost;
+    let protocol_fee = bps_of(share, protocol_fee_bps)?;
+    let creator_fee = bps_of(share, creator_fee_bps)?;
+    require!(
+        protocol_fee.checked_add(creator_fee).ok_or(ClawBetsError::Overflow)? <= share,
+        ClawBetsError::FeeTooHigh
+    );
+
+    Ok(Payout {
+        stake: cost,
+        share,
+        protocol_fee,
+        creator_fee,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOL: u64 = 1_000_000_000;
+
+    #[test]
+    fn buy_from_balanced_pool() {
+        // 10 SOL pool at 50/50, buy YES with 10 SOL
+        let (yes, no, shares) = buy(10 * SOL, 10 * SOL, true, 10 * SOL).unwrap();
+        assert_eq!(no, 20 * SOL);
+        assert_eq!(yes, 5 * SOL);
+        assert_eq!(shares, 15 * SOL);
+        assert_eq!(price_bps(yes, no, true).unwrap(), 8000);
+    }
+
+    #[test]
+    fn buy_is_symmetric() {
+        let (yes, no, shares) = buy(7 * SOL, 3 * SOL, false, SOL).unwrap();
+        let (no_m, yes_m, shares_m) = buy(3 * SOL, 7 * SOL, true, SOL).unwrap();
+        assert_eq!((yes, no, shares), (yes_m, no_m, shares_m));
+    }
+
+    #[test]
+    fn buy_conserves_shares_and_never_shrinks_invariant() {
+        for (yes, no, amount) in [(1_000, 1_000, 7), (999, 3, 1), (5 * SOL, 2 * SOL, 333_333_333), (1, 1, 1)] {
+            for position in [true, false] {
+                let (yes_after, no_after, shares) = buy(yes, no, position, amount).unwrap();
+                // Every unit of collateral mints one YES and one NO share
+                assert_eq!(yes_after + no_after + shares, yes + no + 2 * amount);
+                assert!((yes_after as u128) * (no_after as u128) >= (yes as u128) * (no as u128));
+                // A share never costs more than the unit it redeems for
+                assert!(shares >= amount);
+            }
+        }
+    }
+
+    #[test]
+    fn buying_moves_the_price_against_the_buyer() {
+        let (yes, no, first) = buy(10 * SOL, 10 * SOL, true, SOL).unwrap();
+        let (_, _, second) = buy(yes, no, true, SOL).unwrap();
+        assert!(second < first);
+        assert!(price_bps(yes, no, true).unwrap() > 5000);
+    }
+
+    #[test]
+    fn empty_pool_is_rejected() {
+        assert!(buy(0, 10, true, 1).is_err());
+        assert!(buy(10, 0, false, 1).is_err());
+        assert!(price_bps(0, 0, true).is_err());
+    }
+
+    #[test]
+    fn redemption_charges_fees_on_profit_only() {
+        let payout = redemption(15 * SOL, 10 * SOL, 200, 100).unwrap();
+        assert_eq!(payout.stake, 10 * SOL);
+        assert_eq!(payout.share, 5 * SOL);
+        assert_eq!(payout.protocol_fee, SOL / 10);
+        assert_eq!(payout.creator_fee, SOL / 20);
+        assert_eq!(payout.winnings().unwrap(), 15 * SOL - SOL / 10 - SOL / 20);
+    }
+
+    #[test]
+    fn redemption_without_profit_pays_shares_fee_free() {
+        let payout = redemption(SOL, SOL, 1000, 500).unwrap();
+        assert_eq!(payout.winnings().unwrap(), SOL);
+        assert_eq!(payout.protocol_fee + payout.creator_fee, 0);
+    }
+}
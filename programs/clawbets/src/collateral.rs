@@ -0,0 +1,156 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::spl_token_2022::extension::{BaseStateWithExtensions, ExtensionType, StateWithExtensions};
+use anchor_spl::token_interface::spl_token_2022::state::Mint as MintState;
+use anchor_spl::token_interface::{self, CloseAccount, Mint, TokenAccount, TokenInterface, TransferChecked};
+use crate::errors::ClawBetsError;
+use crate::state::Market;
+
+/// Mint and token program used to move SPL / Token-2022 collateral.
+pub struct TokenCollateral<'a, 'info> {
+    pub mint: &'a InterfaceAccount<'info, Mint>,
+    pub token_program: &'a Interface<'info, TokenInterface>,
+}
+
+impl<'info> TokenCollateral<'_, 'info> {
+    /// `transfer_checked` between two token accounts. Pass `signer_seeds` when the
+    /// authority is a program PDA.
+    pub fn transfer(
+        &self,
+        from: &InterfaceAccount<'info, TokenAccount>,
+        to: &InterfaceAccount<'info, TokenAccount>,
+        authority: AccountInfo<'info>,
+        signer_seeds: &[&[&[u8]]],
+        amount: u64,
+    ) -> Result<()> {
+        token_interface::transfer_checked(
+            CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                TransferChecked {
+                    from: from.to_account_info(),
+                    mint: self.mint.to_account_info(),
+                    to: to.to_account_info(),
+                    authority,
+                },
+                signer_seeds,
+            ),
+            amount,
+            self.mint.decimals,
+        )
+    }
+
+    /// Close an empty token account, sending its rent to `destination`.
+    pub fn close(
+        &self,
+        account: &InterfaceAccount<'info, TokenAccount>,
+        destination: AccountInfo<'info>,
+        authority: AccountInfo<'info>,
+        signer_seeds: &[&[&[u8]]],
+    ) -> Result<()> {
+        token_interface::close_account(CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            CloseAccount {
+                account: account.to_account_info(),
+                destination,
+                authority,
+            },
+            signer_seeds,
+        ))
+    }
+}
+
+/// Check an instruction's optional token accounts against the market's collateral.
+/// Returns `None` for native SOL markets, where the token accounts are ignored.
+pub fn for_market<'a, 'info>(
+    market: &Market,
+    mint: &'a Option<InterfaceAccount<'info, Mint>>,
+    token_program: &'a Option<Interface<'info, TokenInterface>>,
+) -> Result<Option<TokenCollateral<'a, 'info>>> {
+    let Some(collateral_mint) = market.collateral_mint else {
+        return Ok(None);
+    };
+    let mint = required(mint)?;
+    require_keys_eq!(mint.key(), collateral_mint, ClawBetsError::CollateralMintMismatch);
+    Ok(Some(TokenCollateral {
+        mint,
+        token_program: required(token_program)?,
+    }))
+}
+
+/// Token-2022 extensions that make a transfer deliver less than `amount` or run
+/// third-party code, either of which breaks the vault's accounting.
+const UNSUPPORTED_EXTENSIONS: [ExtensionType; 2] = [ExtensionType::TransferFeeConfig, ExtensionType::TransferHook];
+
+/// Reject collateral mints carrying an unsupported Token-2022 extension. Legacy
+/// SPL mints have no extensions and always pass.
+pub fn check_mint_extensions(mint_data: &[u8]) -> Result<()> {
+    let mint = StateWithExtensions::<MintState>::unpack(mint_data)?;
+    let unsupported = mint
+        .get_extension_types()?
+        .iter()
+        .any(|extension| UNSUPPORTED_EXTENSIONS.contains(extension));
+    require!(!unsupported, ClawBetsError::UnsupportedMintExtension);
+    Ok(())
+}
+
+/// Unwrap an optional account that token markets must provide.
+pub fn required<T>(account: &Option<T>) -> Result<&T> {
+    account
+        .as_ref()
+        .ok_or(ClawBetsError::MissingCollateralAccounts.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_spl::token_interface::spl_token_2022::extension::{
+        mint_close_authority::MintCloseAuthority, transfer_fee::TransferFeeConfig, transfer_hook::TransferHook,
+        BaseStateWithExtensionsMut, StateWithExtensionsMut,
+    };
+    use anchor_lang::solana_program::program_pack::Pack;
+
+    fn mint_with(extensions: &[ExtensionType]) -> Vec<u8> {
+        let len = if extensions.is_empty() {
+            MintState::LEN
+        } else {
+            ExtensionType::try_calculate_account_len::<MintState>(extensions).unwrap()
+        };
+        let mut data = vec![0; len];
+        if extensions.is_empty() {
+            MintState::pack(MintState { is_initialized: true, decimals: 6, ..Default::default() }, &mut data).unwrap();
+            return data;
+        }
+        let mut state = StateWithExtensionsMut::<MintState>::unpack_uninitialized(&mut data).unwrap();
+        for extension in extensions {
+            match extension {
+                ExtensionType::TransferFeeConfig => {
+                    state.init_extension::<TransferFeeConfig>(true).unwrap();
+                }
+                ExtensionType::TransferHook => {
+                    state.init_extension::<TransferHook>(true).unwrap();
+                }
+                ExtensionType::MintCloseAuthority => {
+                    state.init_extension::<MintCloseAuthority>(true).unwrap();
+                }
+                _ => unreachable!(),
+            }
+        }
+        state.base = MintState { is_initialized: true, decimals: 6, ..Default::default() };
+        state.pack_base();
+        state.init_account_type().unwrap();
+        data
+    }
+
+    #[test]
+    fn accepts_plain_and_benign_mints() {
+        assert!(check_mint_extensions(&mint_with(&[])).is_ok());
+        assert!(check_mint_extensions(&mint_with(&[ExtensionType::MintCloseAuthority])).is_ok());
+    }
+
+    #[test]
+    fn rejects_transfer_fee_and_hook_mints() {
+        for extension in UNSUPPORTED_EXTENSIONS {
+            let err = check_mint_extensions(&mint_with(&[ExtensionType::MintCloseAuthority, extension])).unwrap_err();
+            assert_eq!(err, ClawBetsError::UnsupportedMintExtension.into());
+        }
+    }
+}
@@ -48,4 +48,142 @@ pub enum ClawBetsError {
     NoWinners,
     #[msg("Market is not in a reclaimable state")]
     MarketNotReclaimable,
+    #[msg("Bet did not lose — use claim_winnings instead")]
+    BetDidNotLose,
+    #[msg("Price exponent out of supported range")]
+    InvalidExponent,
+    #[msg("Price window must be positive, end before the resolution deadline and fit within the feed's max staleness")]
+    InvalidPriceWindow,
+    #[msg("Oracle price was not the first published within the deadline window")]
+    PublishTimeOutsideWindow,
+    #[msg("Max confidence must be between 1 and 10000 basis points")]
+    InvalidConfidenceBound,
+    #[msg("Only the protocol admin can perform this action")]
+    UnauthorizedAdmin,
+    #[msg("Fee exceeds the protocol maximum")]
+    FeeTooHigh,
+    #[msg("Treasury balance too low for this withdrawal")]
+    InsufficientFees,
+    #[msg("No creator fees to claim")]
+    NoCreatorFees,
+    #[msg("Token collateral accounts are required for this market")]
+    MissingCollateralAccounts,
+    #[msg("Collateral mint does not match the market")]
+    CollateralMintMismatch,
+    #[msg("Cannot add to the opposite side of an existing bet")]
+    HedgingNotAllowed,
+    #[msg("Exit penalty must be at most 10000 basis points")]
+    InvalidExitPenalty,
+    #[msg("Bucket bounds must be strictly increasing, with 2 to 8 buckets")]
+    InvalidBuckets,
+    #[msg("Bucket index out of range for this market")]
+    InvalidBucket,
+    #[msg("Scalar floor must be below cap")]
+    InvalidScalarRange,
+    #[msg("Operation not supported for this market type")]
+    InvalidMarketKind,
+    #[msg("Barrier has already been touched")]
+    AlreadyTouched,
+    #[msg("Price update does not show the barrier being touched")]
+    BarrierNotTouched,
+    #[msg("A price update account is required to resolve this market")]
+    MissingPriceUpdate,
+    #[msg("TWAP needs 2 to 32 observations and a max gap within the price window")]
+    InvalidTwapConfig,
+    #[msg("Observation must be published after the previous one")]
+    ObservationOutOfOrder,
+    #[msg("Observation is too far after the previous one")]
+    ObservationGapTooLarge,
+    #[msg("Observation limit reached")]
+    TooManyObservations,
+    #[msg("Not enough observations to compute the TWAP")]
+    NotEnoughObservations,
+    #[msg("Relative markets need a price update for each of their two feeds")]
+    MissingFeedPrice,
+    #[msg("Price update is for a different feed")]
+    FeedMismatch,
+    #[msg("Relative markets need two different feeds")]
+    InvalidFeedPair,
+    #[msg("Start prices have not been recorded")]
+    StartPricesMissing,
+    #[msg("Start prices have already been recorded")]
+    StartPricesAlreadySet,
+    #[msg("Optimistic markets need a rent-exempt bond and a non-zero challenge period")]
+    InvalidOptimisticConfig,
+    #[msg("An outcome has already been proposed")]
+    AlreadyProposed,
+    #[msg("No outcome proposal is pending")]
+    NoPendingProposal,
+    #[msg("Challenge period has ended")]
+    ChallengePeriodOver,
+    #[msg("Challenge period has not ended yet")]
+    ChallengePeriodActive,
+    #[msg("Proposal is not disputed")]
+    NotDisputed,
+    #[msg("Only the market's arbiter can decide this dispute")]
+    UnauthorizedArbiter,
+    #[msg("Account does not match the proposal")]
+    ProposalAccountMismatch,
+    #[msg("Dispute period out of range or challenge bond below rent exemption")]
+    InvalidDisputeParams,
+    #[msg("Market resolution is not in its dispute period")]
+    NotResolving,
+    #[msg("Dispute period has ended")]
+    DisputePeriodOver,
+    #[msg("Dispute period has not ended yet")]
+    DisputePeriodActive,
+    #[msg("Resolution has already been challenged")]
+    AlreadyChallenged,
+    #[msg("Price update settles the market the same way")]
+    ChallengeMatchesResolution,
+    #[msg("Settlement does not fit this market type")]
+    InvalidSettlement,
+    #[msg("Account does not match the challenger")]
+    ChallengerMismatch,
+    #[msg("Feed symbol must be 1-16 characters with a positive max staleness")]
+    InvalidFeedConfig,
+    #[msg("Feed is not whitelisted")]
+    FeedNotWhitelisted,
+    #[msg("Feed is disabled")]
+    FeedDisabled,
+    #[msg("Exponent does not match the feed's registered exponent")]
+    FeedExponentMismatch,
+    #[msg("Settlement price is older than the feed's max staleness allows")]
+    PriceTooStale,
+    #[msg("Signer does not hold the protocol role this action requires")]
+    UnauthorizedRole,
+    #[msg("No admin transfer is pending")]
+    NoPendingAdmin,
+    #[msg("Only the pending admin can accept the transfer")]
+    NotPendingAdmin,
+    #[msg("This operation is paused")]
+    OperationPaused,
+    #[msg("Market is frozen")]
+    MarketFrozen,
+    #[msg("Unknown pause flags")]
+    InvalidPauseFlags,
+    #[msg("Market must be resolved, cancelled, voided or expired to finalize")]
+    MarketNotFinalizable,
+    #[msg("Not every bet on this market has been settled")]
+    UnsettledBets,
+    #[msg("Only the market creator or protocol admin can cancel a market")]
+    UnauthorizedCancel,
+    #[msg("Remaining accounts must be (bet, bettor, reputation) groups, plus the bettor's token account on token markets")]
+    InvalidRemainingAccounts,
+    #[msg("AMM pool must be seeded with liquidity on both sides")]
+    InvalidAmmPool,
+    #[msg("Bet would receive fewer shares than min_shares_out")]
+    SlippageExceeded,
+    #[msg("AMM bets can't be withdrawn before resolution")]
+    AmmWithdrawalUnsupported,
+    #[msg("Collateral mint has a transfer fee or transfer hook extension")]
+    UnsupportedMintExtension,
+    #[msg("Bet was placed after the barrier touch and can only be reclaimed")]
+    LateBet,
+    #[msg("Arbitration deadline has passed")]
+    ArbitrationExpired,
+    #[msg("Account isn't a legacy account of this program")]
+    NotMigratable,
+    #[msg("Account is already on the current layout")]
+    AlreadyMigrated,
 }
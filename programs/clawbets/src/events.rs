@@ -0,0 +1,442 @@
+use anchor_lang::prelude::*;
+use crate::oracle::Settlement;
+use crate::state::{AmmPool, CancelReason, MarketKind, Role, StartPrices, TouchProof, TwapConfig};
+
+/// Layout version carried by every event. Bump when any event's fields change.
+pub const EVENT_VERSION: u8 = 11;
+
+#[event]
+pub struct ProtocolInitialized {
+    pub version: u8,
+    pub admin: Pubkey,
+    pub treasury: Pubkey,
+}
+
+#[event]
+pub struct ProtocolMigrated {
+    pub version: u8,
+    pub admin: Pubkey,
+    pub treasury: Pubkey,
+    pub market_count: u64,
+}
+
+#[event]
+pub struct MarketMigrated {
+    pub version: u8,
+    pub market: Pubkey,
+    pub market_id: u64,
+    pub expected_exponent: i32,
+}
+
+#[event]
+pub struct BetMigrated {
+    pub version: u8,
+    pub market: Pubkey,
+    pub bettor: Pubkey,
+    /// Already-claimed bets are closed instead of migrated
+    pub closed: bool,
+}
+
+#[event]
+pub struct ReputationMigrated {
+    pub version: u8,
+    pub agent: Pubkey,
+}
+
+#[event]
+pub struct MarketCreated {
+    pub version: u8,
+    pub market: Pubkey,
+    pub market_id: u64,
+    pub creator: Pubkey,
+    pub title: String,
+    pub description: String,
+    pub feed_id: [u8; 32],
+    pub target_price: i64,
+    pub expected_exponent: i32,
+    pub target_above: bool,
+    pub max_conf_bps: u16,
+    pub deadline: i64,
+    pub resolution_deadline: i64,
+    pub price_window: i64,
+    pub min_bet: u64,
+    pub max_bet: u64,
+    pub collateral_mint: Option<Pubkey>,
+    pub exit_penalty_bps: u16,
+    pub creator_fee_bps: u16,
+    pub kind: MarketKind,
+    pub twap: Option<TwapConfig>,
+    pub start_prices: Option<StartPrices>,
+    pub amm: Option<AmmPool>,
+    pub created_at: i64,
+}
+
+#[event]
+pub struct BetPlaced {
+    pub version: u8,
+    pub market: Pubkey,
+    pub bettor: Pubkey,
+    /// Amount deposited by this instruction
+    pub amount: u64,
+    /// Cumulative bet amount after the deposit
+    pub bet_amount: u64,
+    pub position: bool,
+    pub bucket: u8,
+    /// False when topping up an existing bet
+    pub is_new: bool,
+    pub total_yes: u64,
+    pub total_no: u64,
+    pub yes_count: u32,
+    pub no_count: u32,
+    pub bucket_totals: Vec<u64>,
+    pub bucket_counts: Vec<u32>,
+    /// Shares bought by this instruction and held after it (AMM markets only)
+    pub shares: u64,
+    pub bet_shares: u64,
+    /// Pool reserves after the trade (AMM markets only)
+    pub amm: Option<AmmPool>,
+    pub placed_at: i64,
+}
+
+#[event]
+pub struct BetWithdrawn {
+    pub version: u8,
+    pub market: Pubkey,
+    pub bettor: Pubkey,
+    pub position: bool,
+    pub bucket: u8,
+    /// Stake removed from the pool
+    pub amount: u64,
+    /// Amount returned to the bettor
+    pub refund: u64,
+    pub penalty: u64,
+    pub total_yes: u64,
+    pub total_no: u64,
+    pub yes_count: u32,
+    pub no_count: u32,
+    pub bucket_totals: Vec<u64>,
+    pub bucket_counts: Vec<u32>,
+    pub penalty_pool: u64,
+    pub withdrawn_at: i64,
+}
+
+#[event]
+pub struct BettingClosed {
+    pub version: u8,
+    pub market: Pubkey,
+    pub market_id: u64,
+    pub closed_at: i64,
+}
+
+#[event]
+pub struct BarrierTouched {
+    pub version: u8,
+    pub market: Pubkey,
+    pub market_id: u64,
+    pub proof: TouchProof,
+}
+
+#[event]
+pub struct ObservationRecorded {
+    pub version: u8,
+    pub market: Pubkey,
+    pub recorder: Pubkey,
+    pub price: i64,
+    pub conf: u64,
+    pub exponent: i32,
+    pub publish_time: i64,
+    /// Observations recorded so far
+    pub count: u16,
+}
+
+#[event]
+pub struct StartPricesRecorded {
+    pub version: u8,
+    pub market: Pubkey,
+    pub market_id: u64,
+    pub start_prices: StartPrices,
+}
+
+#[event]
+pub struct OutcomeProposed {
+    pub version: u8,
+    pub market: Pubkey,
+    pub market_id: u64,
+    pub proposer: Pubkey,
+    pub outcome: bool,
+    pub bond: u64,
+    pub challenge_ends_at: i64,
+}
+
+#[event]
+pub struct OutcomeDisputed {
+    pub version: u8,
+    pub market: Pubkey,
+    pub market_id: u64,
+    pub proposer: Pubkey,
+    pub disputer: Pubkey,
+    pub bond: u64,
+}
+
+#[event]
+pub struct ProposalResolved {
+    pub version: u8,
+    pub market: Pubkey,
+    pub market_id: u64,
+    pub outcome: bool,
+    /// False when an undisputed proposal finalized, true when the arbiter decided
+    pub disputed: bool,
+    /// Proposer or disputer who received the bonds
+    pub bond_recipient: Pubkey,
+    pub bond_paid: u64,
+    pub resolved_at: i64,
+}
+
+#[event]
+pub struct MarketResolved {
+    pub version: u8,
+    pub market: Pubkey,
+    pub market_id: u64,
+    /// Winning side, or None if the market was voided
+    pub outcome: Option<bool>,
+    /// Winning bucket of a categorical market
+    pub winning_bucket: Option<u8>,
+    /// Clamped price offset above the floor of a scalar market
+    pub scalar_offset: Option<u64>,
+    /// Number of observations averaged (TWAP markets only)
+    pub observations: Option<u16>,
+    /// Settlement price of the second feed (relative markets only)
+    pub price_b: Option<i64>,
+    pub exponent_b: Option<i32>,
+    pub price: i64,
+    pub conf: u64,
+    pub exponent: i32,
+    pub publish_time: i64,
+    pub total_yes: u64,
+    pub total_no: u64,
+    pub bucket_totals: Vec<u64>,
+    pub penalty_pool: u64,
+    pub resolved_at: i64,
+    /// Claims open after this time unless the resolution is challenged
+    pub finalizes_at: i64,
+}
+
+#[event]
+pub struct ResolutionChallenged {
+    pub version: u8,
+    pub market: Pubkey,
+    pub market_id: u64,
+    pub challenger: Pubkey,
+    pub bond: u64,
+    /// Settlement the challenger claims
+    pub claim: Settlement,
+    /// Touch proof that settled an untouched barrier market as claimed
+    pub touch: Option<TouchProof>,
+    pub finalizes_at: i64,
+}
+
+#[event]
+pub struct ResolutionOverturned {
+    pub version: u8,
+    pub market: Pubkey,
+    pub market_id: u64,
+    pub arbiter: Pubkey,
+    pub outcome: Option<bool>,
+    pub winning_bucket: Option<u8>,
+    pub scalar_offset: Option<u64>,
+}
+
+#[event]
+pub struct ResolutionFinalized {
+    pub version: u8,
+    pub market: Pubkey,
+    pub market_id: u64,
+    /// True if the market finalized as void and every bet is refunded
+    pub void: bool,
+    pub outcome: Option<bool>,
+    pub winning_bucket: Option<u8>,
+    pub scalar_offset: Option<u64>,
+    /// Challenger or treasury that received the challenge bond, if any
+    pub bond_recipient: Option<Pubkey>,
+    pub finalized_at: i64,
+}
+
+#[event]
+pub struct WinningsClaimed {
+    pub version: u8,
+    pub market: Pubkey,
+    pub bettor: Pubkey,
+    pub stake: u64,
+    /// Amount sent to the bettor (stake + profit)
+    pub winnings: u64,
+    pub protocol_fee: u64,
+    pub creator_fee: u64,
+    /// Creator fees accrued on the market after this claim
+    pub creator_fees: u64,
+}
+
+#[event]
+pub struct WinningsDistributed {
+    pub version: u8,
+    pub market: Pubkey,
+    pub cranker: Pubkey,
+    /// Bets paid in this batch
+    pub paid: u32,
+    /// Pairs skipped as already claimed or not winning
+    pub skipped: u32,
+    /// Total sent to bettors
+    pub winnings: u64,
+    /// Part of the protocol fee paid to the cranker
+    pub crank_reward: u64,
+}
+
+#[event]
+pub struct LossSettled {
+    pub version: u8,
+    pub market: Pubkey,
+    pub bettor: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct BetReclaimed {
+    pub version: u8,
+    pub market: Pubkey,
+    pub bettor: Pubkey,
+    pub stake: u64,
+    /// Amount returned, including any share of the penalty pool
+    pub amount: u64,
+}
+
+#[event]
+pub struct MarketCancelled {
+    pub version: u8,
+    pub market: Pubkey,
+    pub market_id: u64,
+    pub cancelled_by: Pubkey,
+    pub reason: CancelReason,
+    pub cancelled_at: i64,
+}
+
+#[event]
+pub struct MarketFinalized {
+    pub version: u8,
+    pub market: Pubkey,
+    pub market_id: u64,
+    /// Unclaimed creator fees plus unredeemed AMM pool liquidity
+    pub creator_amount: u64,
+    pub dust: u64,
+    pub finalized_at: i64,
+}
+
+#[event]
+pub struct MarketExpired {
+    pub version: u8,
+    pub market: Pubkey,
+    pub market_id: u64,
+    pub expired_at: i64,
+    /// Proposal and dispute bonds returned to their posters
+    pub bonds_returned: u64,
+}
+
+#[event]
+pub struct CreatorFeesClaimed {
+    pub version: u8,
+    pub market: Pubkey,
+    pub creator: Pubkey,
+    pub amount: u64,
+    /// AMM pool shares redeemed along with the fees
+    pub liquidity: u64,
+}
+
+#[event]
+pub struct AdminProposed {
+    pub version: u8,
+    pub admin: Pubkey,
+    pub pending_admin: Pubkey,
+}
+
+#[event]
+pub struct AdminTransferred {
+    pub version: u8,
+    pub old_admin: Pubkey,
+    pub admin: Pubkey,
+}
+
+#[event]
+pub struct RoleUpdated {
+    pub version: u8,
+    pub role: Role,
+    pub old_key: Pubkey,
+    pub key: Pubkey,
+}
+
+#[event]
+pub struct PauseUpdated {
+    pub version: u8,
+    pub old_paused: u8,
+    pub paused: u8,
+}
+
+#[event]
+pub struct MarketFreezeUpdated {
+    pub version: u8,
+    pub market: Pubkey,
+    pub market_id: u64,
+    pub frozen: bool,
+}
+
+#[event]
+pub struct CrankRewardUpdated {
+    pub version: u8,
+    pub old_crank_reward_bps: u16,
+    pub crank_reward_bps: u16,
+}
+
+#[event]
+pub struct FeeUpdated {
+    pub version: u8,
+    pub old_fee_bps: u16,
+    pub fee_bps: u16,
+}
+
+#[event]
+pub struct DisputeParamsUpdated {
+    pub version: u8,
+    pub dispute_period: i64,
+    pub challenge_bond: u64,
+}
+
+#[event]
+pub struct FeedAdded {
+    pub version: u8,
+    pub feed_id: [u8; 32],
+    pub symbol: String,
+    pub exponent: i32,
+    pub max_staleness: i64,
+}
+
+#[event]
+pub struct FeedUpdated {
+    pub version: u8,
+    pub feed_id: [u8; 32],
+    pub symbol: String,
+    pub exponent: i32,
+    pub max_staleness: i64,
+    pub enabled: bool,
+}
+
+#[event]
+pub struct FeedDisabled {
+    pub version: u8,
+    pub feed_id: [u8; 32],
+}
+
+#[event]
+pub struct FeesWithdrawn {
+    pub version: u8,
+    /// None for SOL fees
+    pub mint: Option<Pubkey>,
+    pub recipient: Pubkey,
+    pub amount: u64,
+}
@@ -0,0 +1,41 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::errors::ClawBetsError;
+use crate::events::*;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct AcceptAdmin<'info> {
+    pub pending_admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"protocol"],
+        bump = protocol.bump,
+    )]
+    pub protocol: Account<'info, Protocol>,
+}
+
+/// Second step of an admin transfer, signed by the proposed admin. Delegated
+/// roles stay with their current keys.
+pub fn handler(ctx: Context<AcceptAdmin>) -> Result<()> {
+    let protocol = &mut ctx.accounts.protocol;
+    let pending_admin = protocol.pending_admin.ok_or(ClawBetsError::NoPendingAdmin)?;
+    require!(
+        ctx.accounts.pending_admin.key() == pending_admin,
+        ClawBetsError::NotPendingAdmin
+    );
+
+    let old_admin = protocol.admin;
+    protocol.admin = pending_admin;
+    protocol.pending_admin = None;
+
+    msg!("Admin transferred from {} to {}", old_admin, pending_admin);
+
+    emit_cpi!(AdminTransferred {
+        version: EVENT_VERSION,
+        old_admin,
+        admin: pending_admin,
+    });
+    Ok(())
+}
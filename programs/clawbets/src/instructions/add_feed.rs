@@ -0,0 +1,70 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::errors::ClawBetsError;
+use crate::events::*;
+use crate::oracle;
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(feed_id: [u8; 32])]
+pub struct AddFeed<'info> {
+    #[account(mut)]
+    pub feed_curator: Signer<'info>,
+
+    #[account(
+        seeds = [b"protocol"],
+        bump = protocol.bump,
+        has_one = feed_curator @ ClawBetsError::UnauthorizedRole,
+    )]
+    pub protocol: Account<'info, Protocol>,
+
+    #[account(
+        init,
+        payer = feed_curator,
+        space = 8 + FeedConfig::INIT_SPACE,
+        seeds = [b"feed", feed_id.as_ref()],
+        bump,
+    )]
+    pub feed: Account<'info, FeedConfig>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn validate_feed_config(symbol: &str, exponent: i32, max_staleness: i64) -> Result<()> {
+    require!(
+        !symbol.is_empty() && symbol.len() <= FeedConfig::MAX_SYMBOL_LEN,
+        ClawBetsError::InvalidFeedConfig
+    );
+    require!(max_staleness > 0, ClawBetsError::InvalidFeedConfig);
+    oracle::validate_exponent(exponent)
+}
+
+/// Whitelist a Pyth feed so markets can be created on it.
+pub fn handler(
+    ctx: Context<AddFeed>,
+    feed_id: [u8; 32],
+    symbol: String,
+    exponent: i32,
+    max_staleness: i64,
+) -> Result<()> {
+    validate_feed_config(&symbol, exponent, max_staleness)?;
+
+    let feed = &mut ctx.accounts.feed;
+    feed.feed_id = feed_id;
+    feed.symbol = symbol;
+    feed.exponent = exponent;
+    feed.max_staleness = max_staleness;
+    feed.enabled = true;
+    feed.bump = ctx.bumps.feed;
+
+    msg!("Feed {} whitelisted", feed.symbol);
+
+    emit_cpi!(FeedAdded {
+        version: EVENT_VERSION,
+        feed_id,
+        symbol: feed.symbol.clone(),
+        exponent,
+        max_staleness,
+    });
+    Ok(())
+}
@@ -0,0 +1,112 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use crate::state::*;
+use crate::errors::ClawBetsError;
+use crate::events::*;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct Arbitrate<'info> {
+    /// The market's arbiter, or the protocol's dispute arbiter if it has none
+    pub arbiter: Signer<'info>,
+
+    #[account(
+        seeds = [b"protocol"],
+        bump = protocol.bump,
+    )]
+    pub protocol: Account<'info, Protocol>,
+
+    #[account(
+        mut,
+        seeds = [b"market", market.market_id.to_le_bytes().as_ref()],
+        bump = market.bump,
+    )]
+    pub market: Account<'info, Market>,
+
+    /// CHECK: Bond escrow PDA for this market
+    #[account(
+        mut,
+        seeds = [b"bond", market.key().as_ref()],
+        bump,
+    )]
+    pub bond: SystemAccount<'info>,
+
+    /// CHECK: Proposer — receives both bonds if upheld
+    #[account(mut)]
+    pub proposer: SystemAccount<'info>,
+
+    /// CHECK: Disputer — receives both bonds if the proposal is overturned
+    #[account(mut)]
+    pub disputer: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Decide a disputed optimistic market. The market resolves to `outcome`, and the
+/// side of the dispute that called it correctly takes both bonds.
+pub fn handler(ctx: Context<Arbitrate>, outcome: bool) -> Result<()> {
+    let market_key = ctx.accounts.market.key();
+    let market = &mut ctx.accounts.market;
+    let clock = Clock::get()?;
+
+    // Validations
+    let MarketKind::Optimistic { bond, arbiter, .. } = market.kind else {
+        return err!(ClawBetsError::InvalidMarketKind);
+    };
+    require!(
+        ctx.accounts.arbiter.key() == arbiter.unwrap_or(ctx.accounts.protocol.dispute_arbiter),
+        ClawBetsError::UnauthorizedArbiter
+    );
+    require!(market.status == MarketStatus::Disputed, ClawBetsError::NotDisputed);
+    let deadline = market.arbitration_deadline()?.ok_or(ClawBetsError::NotDisputed)?;
+    require!(clock.unix_timestamp <= deadline, ClawBetsError::ArbitrationExpired);
+    let proposal = market.proposal.ok_or(ClawBetsError::NoPendingProposal)?;
+    let disputer = proposal.disputer.ok_or(ClawBetsError::NotDisputed)?;
+    require!(
+        ctx.accounts.proposer.key() == proposal.proposer && ctx.accounts.disputer.key() == disputer,
+        ClawBetsError::ProposalAccountMismatch
+    );
+
+    // The losing side forfeits its bond to the winner
+    let winner = if outcome == proposal.outcome {
+        ctx.accounts.proposer.to_account_info()
+    } else {
+        ctx.accounts.disputer.to_account_info()
+    };
+    let bond_paid = bond.checked_mul(2).ok_or(ClawBetsError::Overflow)?;
+    let bond_seeds: &[&[u8]] = &[b"bond", market_key.as_ref(), &[proposal.bond_bump]];
+    system_program::transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.bond.to_account_info(),
+                to: winner.clone(),
+            },
+            &[bond_seeds],
+        ),
+        bond_paid,
+    )?;
+
+    market.status = MarketStatus::Resolved;
+    market.outcome = Some(outcome);
+    market.resolved_at = Some(clock.unix_timestamp);
+
+    msg!(
+        "Market {} dispute decided: {} (proposal {})",
+        market.market_id,
+        if outcome { "YES wins" } else { "NO wins" },
+        if outcome == proposal.outcome { "upheld" } else { "overturned" }
+    );
+
+    emit_cpi!(ProposalResolved {
+        version: EVENT_VERSION,
+        market: market.key(),
+        market_id: market.market_id,
+        outcome,
+        disputed: true,
+        bond_recipient: winner.key(),
+        bond_paid,
+        resolved_at: clock.unix_timestamp,
+    });
+    Ok(())
+}
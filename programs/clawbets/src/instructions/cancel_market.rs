@@ -1,32 +1,66 @@
 use anchor_lang::prelude::*;
 use crate::state::*;
 use crate::errors::ClawBetsError;
+use crate::events::*;
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct CancelMarket<'info> {
-    #[account(mut)]
-    pub creator: Signer<'info>,
+    /// Market creator or protocol admin
+    pub authority: Signer<'info>,
 
     #[account(
         mut,
         seeds = [b"market", market.market_id.to_le_bytes().as_ref()],
         bump = market.bump,
-        has_one = creator @ ClawBetsError::UnauthorizedCreator,
     )]
     pub market: Account<'info, Market>,
+
+    #[account(
+        seeds = [b"protocol"],
+        bump = protocol.bump,
+    )]
+    pub protocol: Account<'info, Protocol>,
 }
 
-pub fn handler(ctx: Context<CancelMarket>) -> Result<()> {
+/// Cancel a market so every bettor can reclaim their stake. The admin can cancel
+/// any market before resolution; the creator only an open market with no bets or
+/// one still inside its grace period.
+pub fn handler(ctx: Context<CancelMarket>, reason: CancelReason) -> Result<()> {
     let market = &mut ctx.accounts.market;
+    let authority = ctx.accounts.authority.key();
+    let clock = Clock::get()?;
 
-    require!(market.status == MarketStatus::Open, ClawBetsError::MarketNotOpen);
-    require!(
-        market.yes_count == 0 && market.no_count == 0,
-        ClawBetsError::MarketHasBets
-    );
+    if authority == ctx.accounts.protocol.admin {
+        require!(
+            market.status == MarketStatus::Open || market.status == MarketStatus::Closed,
+            ClawBetsError::MarketNotOpen
+        );
+    } else {
+        require!(authority == market.creator, ClawBetsError::UnauthorizedCancel);
+        require!(market.status == MarketStatus::Open, ClawBetsError::MarketNotOpen);
+        let grace_ends = market
+            .created_at
+            .checked_add(Protocol::CANCEL_GRACE_PERIOD)
+            .ok_or(ClawBetsError::Overflow)?;
+        require!(
+            market.bet_count()? == 0 || clock.unix_timestamp <= grace_ends,
+            ClawBetsError::MarketHasBets
+        );
+    }
 
     market.status = MarketStatus::Cancelled;
+    market.cancel_reason = Some(reason);
+
+    msg!("Market {} cancelled by {}: {:?}", market.market_id, authority, reason);
 
-    msg!("Market {} cancelled", market.market_id);
+    emit_cpi!(MarketCancelled {
+        version: EVENT_VERSION,
+        market: market.key(),
+        market_id: market.market_id,
+        cancelled_by: authority,
+        reason,
+        cancelled_at: clock.unix_timestamp,
+    });
     Ok(())
 }
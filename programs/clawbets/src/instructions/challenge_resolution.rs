@@ -0,0 +1,133 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use pyth_solana_receiver_sdk::price_update::PriceUpdateV2;
+use crate::state::*;
+use crate::errors::ClawBetsError;
+use crate::events::*;
+use crate::oracle::{self, Settlement};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ChallengeResolution<'info> {
+    #[account(mut)]
+    pub challenger: Signer<'info>,
+
+    #[account(
+        seeds = [b"protocol"],
+        bump = protocol.bump,
+    )]
+    pub protocol: Account<'info, Protocol>,
+
+    #[account(
+        mut,
+        seeds = [b"market", market.market_id.to_le_bytes().as_ref()],
+        bump = market.bump,
+    )]
+    pub market: Account<'info, Market>,
+
+    /// CHECK: Bond escrow PDA for this market
+    #[account(
+        mut,
+        seeds = [b"bond", market.key().as_ref()],
+        bump,
+    )]
+    pub bond: SystemAccount<'info>,
+
+    /// Touch proof against an untouched barrier market, which settles it YES at once
+    pub price_update: Option<Account<'info, PriceUpdateV2>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// During the dispute period, anyone can post the protocol's challenge bond with a
+/// claim that the market settles differently, restarting the dispute period so the
+/// dispute arbiter can review it. A touch proof against an untouched barrier settles
+/// the market as claimed right away; any other claim stands only if the arbiter
+/// adopts it with `overturn_resolution`.
+pub fn handler(ctx: Context<ChallengeResolution>, claim: Settlement) -> Result<()> {
+    let market = &mut ctx.accounts.market;
+    let clock = Clock::get()?;
+
+    // Validations
+    require!(market.status == MarketStatus::Resolving, ClawBetsError::NotResolving);
+    let finalizes_at = market.finalizes_at.ok_or(ClawBetsError::NotResolving)?;
+    require!(clock.unix_timestamp < finalizes_at, ClawBetsError::DisputePeriodOver);
+    require!(market.challenge.is_none(), ClawBetsError::AlreadyChallenged);
+    market.validate_settlement(claim)?;
+    require!(claim != market.settlement(), ClawBetsError::ChallengeMatchesResolution);
+
+    let touch = match &ctx.accounts.price_update {
+        Some(price_update) => {
+            // An untouched barrier is overturned by proof that it was touched
+            require!(
+                market.is_barrier() && market.touch.is_none(),
+                ClawBetsError::InvalidMarketKind
+            );
+            require!(claim == Settlement::Outcome(true), ClawBetsError::InvalidSettlement);
+            let price = oracle::check_touch(market, price_update)?;
+            let proof = TouchProof {
+                price: price.price,
+                conf: price.conf,
+                exponent: price.exponent,
+                publish_time: price.publish_time,
+                price_update: price_update.key(),
+                posted_slot: price_update.posted_slot,
+                recorder: ctx.accounts.challenger.key(),
+                recorded_at: clock.unix_timestamp,
+            };
+            market.touch = Some(proof);
+            market.apply_settlement(claim);
+            market.resolved_price = Some(price.price);
+            market.resolved_exponent = Some(price.exponent);
+            market.resolved_publish_time = Some(price.publish_time);
+            Some(proof)
+        }
+        None => None,
+    };
+
+    let bond = ctx.accounts.protocol.challenge_bond;
+    system_program::transfer(
+        CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.challenger.to_account_info(),
+                to: ctx.accounts.bond.to_account_info(),
+            },
+        ),
+        bond,
+    )?;
+
+    let finalizes_at = clock
+        .unix_timestamp
+        .checked_add(ctx.accounts.protocol.dispute_period)
+        .ok_or(ClawBetsError::Overflow)?;
+    market.finalizes_at = Some(finalizes_at);
+    market.challenge = Some(ResolutionChallenge {
+        challenger: ctx.accounts.challenger.key(),
+        settlement: claim,
+        bond,
+        challenged_at: clock.unix_timestamp,
+        bond_bump: ctx.bumps.bond,
+    });
+
+    msg!(
+        "Market {} resolution challenged by {}: claims {}{}, final at {}",
+        market.market_id,
+        ctx.accounts.challenger.key(),
+        claim.describe(),
+        if touch.is_some() { " (touch proven)" } else { "" },
+        finalizes_at
+    );
+
+    emit_cpi!(ResolutionChallenged {
+        version: EVENT_VERSION,
+        market: market.key(),
+        market_id: market.market_id,
+        challenger: ctx.accounts.challenger.key(),
+        bond,
+        claim,
+        touch,
+        finalizes_at,
+    });
+    Ok(())
+}
@@ -0,0 +1,141 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::state::*;
+use crate::errors::ClawBetsError;
+use crate::events::*;
+use crate::collateral;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ClaimCreatorFees<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"market", market.market_id.to_le_bytes().as_ref()],
+        bump = market.bump,
+        has_one = creator @ ClawBetsError::UnauthorizedCreator,
+    )]
+    pub market: Account<'info, Market>,
+
+    #[account(
+        seeds = [b"protocol"],
+        bump = protocol.bump,
+    )]
+    pub protocol: Account<'info, Protocol>,
+
+    /// CHECK: Vault PDA holding escrowed SOL
+    #[account(
+        mut,
+        seeds = [b"vault", market.key().as_ref()],
+        bump = market.vault_bump,
+    )]
+    pub vault: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"reputation", creator.key().as_ref()],
+        bump = reputation.bump,
+    )]
+    pub reputation: Account<'info, AgentReputation>,
+
+    /// Collateral mint (token markets only)
+    pub collateral_mint: Option<InterfaceAccount<'info, Mint>>,
+
+    /// Token vault owned by the vault PDA (token markets only)
+    #[account(
+        mut,
+        seeds = [b"token_vault", market.key().as_ref()],
+        bump,
+    )]
+    pub vault_token: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Creator's token account (token markets only)
+    #[account(
+        mut,
+        token::mint = collateral_mint,
+        token::authority = creator,
+    )]
+    pub creator_token: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Pays out creator fees accrued from winners' claims so far. Can be called again
+/// as more winners claim. On AMM markets the first call also redeems the pool's
+/// winning shares, or its seed liquidity if the market refunds every bet.
+pub fn handler(ctx: Context<ClaimCreatorFees>) -> Result<()> {
+    let market_key = ctx.accounts.market.key();
+    let market = &mut ctx.accounts.market;
+
+    ctx.accounts.protocol.require_unpaused(Protocol::PAUSE_CLAIM)?;
+    market.require_unfrozen()?;
+    let liquidity = market.lp_claim();
+    require!(
+        market.status == MarketStatus::Resolved || liquidity > 0,
+        ClawBetsError::MarketNotResolved
+    );
+
+    let fees = market.creator_fees;
+    let amount = fees.checked_add(liquidity).ok_or(ClawBetsError::Overflow)?;
+    require!(amount > 0, ClawBetsError::NoCreatorFees);
+
+    // Transfer from vault PDA to creator
+    let vault_seeds: &[&[u8]] = &[b"vault", market_key.as_ref(), &[market.vault_bump]];
+    match collateral::for_market(market, &ctx.accounts.collateral_mint, &ctx.accounts.token_program)? {
+        None => {
+            system_program::transfer(
+                CpiContext::new_with_signer(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.vault.to_account_info(),
+                        to: ctx.accounts.creator.to_account_info(),
+                    },
+                    &[vault_seeds],
+                ),
+                amount,
+            )?;
+        }
+        Some(token) => {
+            token.transfer(
+                collateral::required(&ctx.accounts.vault_token)?,
+                collateral::required(&ctx.accounts.creator_token)?,
+                ctx.accounts.vault.to_account_info(),
+                &[vault_seeds],
+                amount,
+            )?;
+        }
+    }
+
+    market.creator_fees = 0;
+    if let Some(pool) = market.amm.as_mut() {
+        pool.redeemed = true;
+    }
+
+    // Update reputation
+    let rep = &mut ctx.accounts.reputation;
+    if market.collateral_mint.is_none() {
+        rep.creator_fees_earned = rep.creator_fees_earned.checked_add(fees).ok_or(ClawBetsError::Overflow)?;
+    }
+    rep.last_active = Clock::get()?.unix_timestamp;
+
+    msg!(
+        "Creator claimed {} of fees and {} of pool liquidity from market {}",
+        fees,
+        liquidity,
+        market.market_id
+    );
+
+    emit_cpi!(CreatorFeesClaimed {
+        version: EVENT_VERSION,
+        market: market.key(),
+        creator: market.creator,
+        amount: fees,
+        liquidity,
+    });
+    Ok(())
+}
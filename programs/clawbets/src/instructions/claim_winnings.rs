@@ -1,13 +1,19 @@
 use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 use crate::state::*;
 use crate::errors::ClawBetsError;
+use crate::events::*;
+use crate::collateral;
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct ClaimWinnings<'info> {
     #[account(mut)]
     pub bettor: Signer<'info>,
 
     #[account(
+        mut,
         seeds = [b"market", market.market_id.to_le_bytes().as_ref()],
         bump = market.bump,
     )]
@@ -19,6 +25,7 @@ pub struct ClaimWinnings<'info> {
         bump = bet.bump,
         has_one = bettor,
         has_one = market,
+        close = bettor,
     )]
     pub bet: Account<'info, Bet>,
 
@@ -37,74 +44,151 @@ pub struct ClaimWinnings<'info> {
     )]
     pub reputation: Account<'info, AgentReputation>,
 
+    #[account(
+        mut,
+        seeds = [b"protocol"],
+        bump = protocol.bump,
+    )]
+    pub protocol: Account<'info, Protocol>,
+
+    /// CHECK: Treasury PDA receiving the protocol fee
+    #[account(
+        mut,
+        seeds = [b"treasury"],
+        bump = protocol.treasury_bump,
+    )]
+    pub treasury: SystemAccount<'info>,
+
+    /// Collateral mint (token markets only)
+    pub collateral_mint: Option<InterfaceAccount<'info, Mint>>,
+
+    /// Token vault owned by the vault PDA (token markets only)
+    #[account(
+        mut,
+        seeds = [b"token_vault", market.key().as_ref()],
+        bump,
+    )]
+    pub vault_token: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Bettor's token account (token markets only)
+    #[account(
+        mut,
+        token::mint = collateral_mint,
+        token::authority = bettor,
+    )]
+    pub bettor_token: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Treasury's associated token account (token markets only)
+    #[account(
+        mut,
+        associated_token::mint = collateral_mint,
+        associated_token::authority = treasury,
+        associated_token::token_program = token_program,
+    )]
+    pub treasury_token: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+
     pub system_program: Program<'info, System>,
 }
 
 pub fn handler(ctx: Context<ClaimWinnings>) -> Result<()> {
-    let market = &ctx.accounts.market;
-    let bet = &mut ctx.accounts.bet;
+    let market_key = ctx.accounts.market.key();
+    let market = &mut ctx.accounts.market;
+    let bet = &ctx.accounts.bet;
 
     // Validations
+    ctx.accounts.protocol.require_unpaused(Protocol::PAUSE_CLAIM)?;
+    market.require_unfrozen()?;
     require!(market.status == MarketStatus::Resolved, ClawBetsError::MarketNotResolved);
-    require!(!bet.claimed, ClawBetsError::AlreadyClaimed);
-
-    let outcome = market.outcome.ok_or(ClawBetsError::MarketNotResolved)?;
-    require!(bet.position == outcome, ClawBetsError::BetDidNotWin);
-
-    // Calculate winnings: proportional share of the losing pool + original bet
-    let (winning_pool, losing_pool) = if outcome {
-        (market.total_yes, market.total_no)
-    } else {
-        (market.total_no, market.total_yes)
-    };
-
-    // If no one bet on the winning side, there are no winners to claim.
-    // Losers should use reclaim_bet instead.
-    require!(winning_pool > 0, ClawBetsError::NoWinners);
-
-    // Winnings = bet_amount + (bet_amount / winning_pool) * losing_pool
-    // Use u128 to avoid overflow, with safe truncation check
-    let share_128 = (bet.amount as u128)
-        .checked_mul(losing_pool as u128)
-        .ok_or(ClawBetsError::Overflow)?
-        .checked_div(winning_pool as u128)
-        .ok_or(ClawBetsError::Overflow)?;
-    let share: u64 = u64::try_from(share_128).map_err(|_| ClawBetsError::Overflow)?;
-    let winnings = bet.amount
-        .checked_add(share)
-        .ok_or(ClawBetsError::Overflow)?;
-
-    // Transfer from vault PDA to bettor
-    **ctx.accounts.vault.to_account_info().try_borrow_mut_lamports()? -= winnings;
-    **ctx.accounts.bettor.to_account_info().try_borrow_mut_lamports()? += winnings;
+    require!(market.is_winning_bet(bet)?, ClawBetsError::BetDidNotWin);
+
+    let protocol = &mut ctx.accounts.protocol;
+    let payout = market.winning_payout(bet, protocol.fee_bps)?;
+    let winnings = payout.winnings()?;
+    let fee = payout.protocol_fee;
+
+    let vault_seeds: &[&[u8]] = &[b"vault", market_key.as_ref(), &[market.vault_bump]];
+    match collateral::for_market(market, &ctx.accounts.collateral_mint, &ctx.accounts.token_program)? {
+        None => {
+            // Transfer winnings to the bettor and the fee to the treasury, signed by the vault PDA
+            for (to, amount) in [
+                (ctx.accounts.bettor.to_account_info(), winnings),
+                (ctx.accounts.treasury.to_account_info(), fee),
+            ] {
+                if amount > 0 {
+                    system_program::transfer(
+                        CpiContext::new_with_signer(
+                            ctx.accounts.system_program.to_account_info(),
+                            system_program::Transfer {
+                                from: ctx.accounts.vault.to_account_info(),
+                                to,
+                            },
+                            &[vault_seeds],
+                        ),
+                        amount,
+                    )?;
+                }
+            }
+            protocol.fees_collected = protocol.fees_collected.checked_add(fee).ok_or(ClawBetsError::Overflow)?;
+        }
+        Some(token) => {
+            let vault_token = collateral::required(&ctx.accounts.vault_token)?;
+
+            // Transfer from token vault to bettor, signed by the vault PDA
+            token.transfer(
+                vault_token,
+                collateral::required(&ctx.accounts.bettor_token)?,
+                ctx.accounts.vault.to_account_info(),
+                &[vault_seeds],
+                winnings,
+            )?;
+
+            // Transfer fee from token vault to treasury
+            if fee > 0 {
+                token.transfer(
+                    vault_token,
+                    collateral::required(&ctx.accounts.treasury_token)?,
+                    ctx.accounts.vault.to_account_info(),
+                    &[vault_seeds],
+                    fee,
+                )?;
+            }
+        }
+    }
+    let is_native = market.collateral_mint.is_none();
 
-    // We don't need CPI for PDA-to-user lamport transfer when vault is a SystemAccount PDA
-    // The above direct lamport manipulation works for PDAs we own
+    // Creator fee stays in the vault until claim_creator_fees
+    market.creator_fees = market
+        .creator_fees
+        .checked_add(payout.creator_fee)
+        .ok_or(ClawBetsError::Overflow)?;
 
-    bet.claimed = true;
+    market.record_settled_bet()?;
 
     // Update reputation
-    let rep = &mut ctx.accounts.reputation;
-    let profit = winnings.checked_sub(bet.amount).unwrap_or(0);
-    rep.wins = rep.wins.checked_add(1).ok_or(ClawBetsError::Overflow)?;
-    rep.total_won = rep.total_won.checked_add(profit).ok_or(ClawBetsError::Overflow)?;
-    
-    // Recalculate accuracy
-    let total = rep.wins.checked_add(rep.losses).ok_or(ClawBetsError::Overflow)?;
-    if total > 0 {
-        rep.accuracy_bps = ((rep.wins as u64)
-            .checked_mul(10000)
-            .ok_or(ClawBetsError::Overflow)?
-            .checked_div(total as u64)
-            .ok_or(ClawBetsError::Overflow)?) as u16;
-    }
-    rep.last_active = Clock::get()?.unix_timestamp;
+    ctx.accounts
+        .reputation
+        .record_claim(bet, &payout, is_native, Clock::get()?.unix_timestamp)?;
 
     msg!(
-        "Claimed {} lamports from market {} (profit: {} lamports)",
+        "Claimed {} {} from market {} (profit: {}, fee: {})",
         winnings,
+        if is_native { "lamports" } else { "tokens" },
         market.market_id,
-        profit
+        payout.profit(),
+        fee
     );
+
+    emit_cpi!(WinningsClaimed {
+        version: EVENT_VERSION,
+        market: market.key(),
+        bettor: bet.bettor,
+        stake: payout.stake,
+        winnings,
+        protocol_fee: payout.protocol_fee,
+        creator_fee: payout.creator_fee,
+        creator_fees: market.creator_fees,
+    });
     Ok(())
 }
@@ -1,7 +1,9 @@
 use anchor_lang::prelude::*;
 use crate::state::*;
 use crate::errors::ClawBetsError;
+use crate::events::*;
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct CloseBetting<'info> {
     #[account(mut)]
@@ -31,5 +33,12 @@ pub fn handler(ctx: Context<CloseBetting>) -> Result<()> {
     market.status = MarketStatus::Closed;
 
     msg!("Market {} betting closed — awaiting resolution", market.market_id);
+
+    emit_cpi!(BettingClosed {
+        version: EVENT_VERSION,
+        market: market.key(),
+        market_id: market.market_id,
+        closed_at: clock.unix_timestamp,
+    });
     Ok(())
 }
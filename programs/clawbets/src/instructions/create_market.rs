@@ -1,7 +1,14 @@
 use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use pyth_solana_receiver_sdk::price_update::PriceUpdateV2;
 use crate::state::*;
 use crate::errors::ClawBetsError;
+use crate::events::*;
+use crate::{collateral, oracle};
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct CreateMarket<'info> {
     #[account(mut)]
@@ -40,30 +47,201 @@ pub struct CreateMarket<'info> {
     )]
     pub reputation: Account<'info, AgentReputation>,
 
+    /// CHECK: Treasury PDA collecting protocol fees
+    #[account(
+        seeds = [b"treasury"],
+        bump = protocol.treasury_bump,
+    )]
+    pub treasury: SystemAccount<'info>,
+
+    /// Start price of `feed_id` (relative markets only — or record it later with snapshot_start)
+    pub start_price_update: Option<Account<'info, PriceUpdateV2>>,
+
+    /// Start price of the second feed (relative markets only)
+    pub start_price_update_b: Option<Account<'info, PriceUpdateV2>>,
+
+    /// Whitelist entry for `feed_id` (every market except optimistic ones)
+    #[account(
+        seeds = [b"feed", feed.feed_id.as_ref()],
+        bump = feed.bump,
+    )]
+    pub feed: Option<Account<'info, FeedConfig>>,
+
+    /// Whitelist entry for the second feed (relative markets only)
+    #[account(
+        seeds = [b"feed", feed_b.feed_id.as_ref()],
+        bump = feed_b.bump,
+    )]
+    pub feed_b: Option<Account<'info, FeedConfig>>,
+
+    /// Collateral mint — omit for a native SOL market
+    pub collateral_mint: Option<InterfaceAccount<'info, Mint>>,
+
+    /// Token vault owned by the vault PDA (token markets only)
+    #[account(
+        init,
+        payer = creator,
+        seeds = [b"token_vault", market.key().as_ref()],
+        bump,
+        token::mint = collateral_mint,
+        token::authority = vault,
+        token::token_program = token_program,
+    )]
+    pub vault_token: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Treasury's associated token account for this mint (token markets only)
+    #[account(
+        init_if_needed,
+        payer = creator,
+        associated_token::mint = collateral_mint,
+        associated_token::authority = treasury,
+        associated_token::token_program = token_program,
+    )]
+    pub treasury_token: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Creator's token account seeding the AMM pool (token AMM markets only)
+    #[account(
+        mut,
+        token::mint = collateral_mint,
+        token::authority = creator,
+    )]
+    pub creator_token: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+
+    pub associated_token_program: Option<Program<'info, AssociatedToken>>,
+
     pub system_program: Program<'info, System>,
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn handler(
     ctx: Context<CreateMarket>,
     title: String,
     description: String,
     feed_id: [u8; 32],
     target_price: i64,
+    expected_exponent: i32,
     target_above: bool,
+    max_conf_bps: u16,
     deadline: i64,
     resolution_deadline: i64,
+    price_window: i64,
     min_bet: u64,
     max_bet: u64,
+    creator_fee_bps: u16,
+    exit_penalty_bps: u16,
+    kind: MarketKind,
+    twap: Option<TwapConfig>,
+    amm_liquidity: Option<u64>,
 ) -> Result<()> {
     // Validations
+    ctx.accounts.protocol.require_unpaused(Protocol::PAUSE_CREATE)?;
     require!(title.len() <= 128, ClawBetsError::TitleTooLong);
     require!(description.len() <= 512, ClawBetsError::DescriptionTooLong);
 
     let clock = Clock::get()?;
     require!(deadline > clock.unix_timestamp, ClawBetsError::DeadlineInPast);
     require!(resolution_deadline > deadline, ClawBetsError::InvalidResolutionDeadline);
+    require!(
+        price_window > 0 && price_window <= resolution_deadline - deadline,
+        ClawBetsError::InvalidPriceWindow
+    );
     require!(min_bet > 0, ClawBetsError::InvalidMinBet);
     require!(max_bet >= min_bet, ClawBetsError::InvalidMaxBet);
+    require!(
+        creator_fee_bps <= Protocol::MAX_CREATOR_FEE_BPS,
+        ClawBetsError::FeeTooHigh
+    );
+    require!(exit_penalty_bps <= 10000, ClawBetsError::InvalidExitPenalty);
+    let bucket_count = match &kind {
+        MarketKind::Binary => 0,
+        MarketKind::Categorical { bounds } => {
+            require!(
+                !bounds.is_empty() && bounds.len() < MAX_BUCKETS,
+                ClawBetsError::InvalidBuckets
+            );
+            require!(
+                bounds.windows(2).all(|pair| pair[0] < pair[1]),
+                ClawBetsError::InvalidBuckets
+            );
+            bounds.len() + 1
+        }
+        MarketKind::Scalar { floor, cap } => {
+            require!(floor < cap, ClawBetsError::InvalidScalarRange);
+            0
+        }
+        MarketKind::Barrier => 0,
+        MarketKind::Relative { feed_b } => {
+            require!(*feed_b != feed_id, ClawBetsError::InvalidFeedPair);
+            0
+        }
+        MarketKind::Optimistic { bond, challenge_period, .. } => {
+            // The bond escrow starts empty, so one bond must cover its rent exemption
+            require!(
+                *bond >= Rent::get()?.minimum_balance(0) && *challenge_period > 0,
+                ClawBetsError::InvalidOptimisticConfig
+            );
+            0
+        }
+    };
+    if let Some(twap) = &twap {
+        require!(
+            matches!(kind, MarketKind::Binary | MarketKind::Categorical { .. } | MarketKind::Scalar { .. }),
+            ClawBetsError::InvalidMarketKind
+        );
+        require!(
+            twap.min_observations >= 2 && twap.min_observations as usize <= MAX_OBSERVATIONS,
+            ClawBetsError::InvalidTwapConfig
+        );
+        require!(
+            twap.max_gap > 0 && twap.max_gap <= price_window,
+            ClawBetsError::InvalidTwapConfig
+        );
+    }
+    if let Some(liquidity) = amm_liquidity {
+        // The pool trades YES against NO, so only two-outcome markets can use it
+        require!(
+            matches!(
+                kind,
+                MarketKind::Binary | MarketKind::Barrier | MarketKind::Relative { .. } | MarketKind::Optimistic { .. }
+            ),
+            ClawBetsError::InvalidMarketKind
+        );
+        require!(liquidity > 0, ClawBetsError::InvalidAmmPool);
+    }
+    oracle::validate_exponent(expected_exponent)?;
+
+    // Price markets must use whitelisted feeds (AUDIT M-2), and a price from
+    // anywhere in the window must be fresh enough for each feed
+    let mut max_staleness = 0;
+    if !matches!(kind, MarketKind::Optimistic { .. }) {
+        let feed = ctx.accounts.feed.as_ref().ok_or(ClawBetsError::FeedNotWhitelisted)?;
+        require!(feed.feed_id == feed_id, ClawBetsError::FeedNotWhitelisted);
+        oracle::check_feed(feed, expected_exponent)?;
+        require!(price_window <= feed.max_staleness, ClawBetsError::InvalidPriceWindow);
+        max_staleness = feed.max_staleness;
+    }
+    if let MarketKind::Relative { feed_b } = &kind {
+        let feed = ctx.accounts.feed_b.as_ref().ok_or(ClawBetsError::FeedNotWhitelisted)?;
+        require!(feed.feed_id == *feed_b, ClawBetsError::FeedNotWhitelisted);
+        // Returns are compared across exponents, so the second feed may use its own
+        require!(feed.enabled, ClawBetsError::FeedDisabled);
+        require!(price_window <= feed.max_staleness, ClawBetsError::InvalidPriceWindow);
+        max_staleness = max_staleness.min(feed.max_staleness);
+    }
+    require!(
+        max_conf_bps > 0 && max_conf_bps <= 10000,
+        ClawBetsError::InvalidConfidenceBound
+    );
+
+    // Token markets need their vault and treasury accounts created up front
+    let collateral_mint = ctx.accounts.collateral_mint.as_ref().map(|mint| mint.key());
+    if let Some(mint) = &ctx.accounts.collateral_mint {
+        collateral::check_mint_extensions(&mint.to_account_info().data.borrow())?;
+        collateral::required(&ctx.accounts.vault_token)?;
+        collateral::required(&ctx.accounts.treasury_token)?;
+    }
 
     let protocol = &mut ctx.accounts.protocol;
     let market = &mut ctx.accounts.market;
@@ -74,11 +252,20 @@ pub fn handler(
     market.description = description;
     market.feed_id = feed_id;
     market.target_price = target_price;
+    market.expected_exponent = expected_exponent;
     market.target_above = target_above;
+    market.max_conf_bps = max_conf_bps;
     market.deadline = deadline;
     market.resolution_deadline = resolution_deadline;
+    market.price_window = price_window;
+    market.max_staleness = max_staleness;
     market.min_bet = min_bet;
     market.max_bet = max_bet;
+    market.collateral_mint = collateral_mint;
+    market.exit_penalty_bps = exit_penalty_bps;
+    market.penalty_pool = 0;
+    market.creator_fee_bps = creator_fee_bps;
+    market.creator_fees = 0;
     market.total_yes = 0;
     market.total_no = 0;
     market.yes_count = 0;
@@ -86,10 +273,68 @@ pub fn handler(
     market.status = MarketStatus::Open;
     market.outcome = None;
     market.resolved_price = None;
+    market.resolved_exponent = None;
+    market.resolved_publish_time = None;
     market.resolved_at = None;
     market.created_at = clock.unix_timestamp;
     market.bump = ctx.bumps.market;
     market.vault_bump = ctx.bumps.vault;
+    market.kind = kind;
+    market.bucket_totals = vec![0; bucket_count];
+    market.bucket_counts = vec![0; bucket_count];
+    market.winning_bucket = None;
+    market.scalar_offset = None;
+    market.touch = None;
+    market.twap = twap;
+    market.start_prices = None;
+    market.proposal = None;
+    market.finalizes_at = None;
+    market.challenge = None;
+    market.frozen = false;
+    market.settled_bets = 0;
+    market.cancel_reason = None;
+    market.amm = amm_liquidity.map(|liquidity| AmmPool {
+        yes_reserve: liquidity,
+        no_reserve: liquidity,
+        liquidity,
+        redeemed: false,
+    });
+
+    // The creator's seed mints the pool's first YES and NO shares
+    if let Some(liquidity) = amm_liquidity {
+        match collateral::for_market(market, &ctx.accounts.collateral_mint, &ctx.accounts.token_program)? {
+            None => system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.creator.to_account_info(),
+                        to: ctx.accounts.vault.to_account_info(),
+                    },
+                ),
+                liquidity,
+            )?,
+            Some(token) => token.transfer(
+                collateral::required(&ctx.accounts.creator_token)?,
+                collateral::required(&ctx.accounts.vault_token)?,
+                ctx.accounts.creator.to_account_info(),
+                &[],
+                liquidity,
+            )?,
+        }
+    }
+
+    // Relative markets can snapshot their start prices right away
+    match (&ctx.accounts.start_price_update, &ctx.accounts.start_price_update_b) {
+        (Some(price_update_a), Some(price_update_b)) => {
+            let (price_a, price_b) = oracle::check_start_prices(market, price_update_a, price_update_b)?;
+            market.start_prices = Some(StartPrices {
+                a: oracle::snapshot(&price_a),
+                b: oracle::snapshot(&price_b),
+            });
+        }
+        (None, None) => {}
+        _ => return err!(ClawBetsError::MissingFeedPrice),
+    }
 
     // Update protocol
     protocol.market_count = protocol.market_count.checked_add(1).ok_or(ClawBetsError::Overflow)?;
@@ -104,5 +349,32 @@ pub fn handler(
     rep.last_active = clock.unix_timestamp;
 
     msg!("Market {} created: {}", market.market_id, market.title);
+
+    emit_cpi!(MarketCreated {
+        version: EVENT_VERSION,
+        market: market.key(),
+        market_id: market.market_id,
+        creator: market.creator,
+        title: market.title.clone(),
+        description: market.description.clone(),
+        feed_id: market.feed_id,
+        target_price: market.target_price,
+        expected_exponent: market.expected_exponent,
+        target_above: market.target_above,
+        max_conf_bps: market.max_conf_bps,
+        deadline: market.deadline,
+        resolution_deadline: market.resolution_deadline,
+        price_window: market.price_window,
+        min_bet: market.min_bet,
+        max_bet: market.max_bet,
+        collateral_mint: market.collateral_mint,
+        exit_penalty_bps: market.exit_penalty_bps,
+        creator_fee_bps: market.creator_fee_bps,
+        kind: market.kind.clone(),
+        twap: market.twap,
+        start_prices: market.start_prices,
+        amm: market.amm,
+        created_at: market.created_at,
+    });
     Ok(())
 }
@@ -0,0 +1,39 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::errors::ClawBetsError;
+use crate::events::*;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct DisableFeed<'info> {
+    pub feed_curator: Signer<'info>,
+
+    #[account(
+        seeds = [b"protocol"],
+        bump = protocol.bump,
+        has_one = feed_curator @ ClawBetsError::UnauthorizedRole,
+    )]
+    pub protocol: Account<'info, Protocol>,
+
+    #[account(
+        mut,
+        seeds = [b"feed", feed.feed_id.as_ref()],
+        bump = feed.bump,
+    )]
+    pub feed: Account<'info, FeedConfig>,
+}
+
+/// Stop new markets from using a feed. Open markets on it can no longer resolve
+/// and fall back to expiry refunds.
+pub fn handler(ctx: Context<DisableFeed>) -> Result<()> {
+    let feed = &mut ctx.accounts.feed;
+    feed.enabled = false;
+
+    msg!("Feed {} disabled", feed.symbol);
+
+    emit_cpi!(FeedDisabled {
+        version: EVENT_VERSION,
+        feed_id: feed.feed_id,
+    });
+    Ok(())
+}
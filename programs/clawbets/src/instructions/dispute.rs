@@ -0,0 +1,84 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use crate::state::*;
+use crate::errors::ClawBetsError;
+use crate::events::*;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct Dispute<'info> {
+    #[account(mut)]
+    pub disputer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"market", market.market_id.to_le_bytes().as_ref()],
+        bump = market.bump,
+    )]
+    pub market: Account<'info, Market>,
+
+    /// CHECK: Bond escrow PDA for this market
+    #[account(
+        mut,
+        seeds = [b"bond", market.key().as_ref()],
+        bump,
+    )]
+    pub bond: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Challenge a pending proposal by posting a matching bond. The dispute escalates
+/// to the market's arbiter, and whoever loses it forfeits their bond.
+pub fn handler(ctx: Context<Dispute>) -> Result<()> {
+    let market = &mut ctx.accounts.market;
+    let clock = Clock::get()?;
+
+    // Validations
+    let (bond, challenge_period) = market
+        .optimistic_terms()
+        .ok_or(ClawBetsError::InvalidMarketKind)?;
+    require!(market.status == MarketStatus::Proposed, ClawBetsError::NoPendingProposal);
+    let mut proposal = market.proposal.ok_or(ClawBetsError::NoPendingProposal)?;
+    let challenge_ends_at = proposal
+        .proposed_at
+        .checked_add(challenge_period)
+        .ok_or(ClawBetsError::Overflow)?;
+    require!(
+        clock.unix_timestamp < challenge_ends_at,
+        ClawBetsError::ChallengePeriodOver
+    );
+
+    system_program::transfer(
+        CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.disputer.to_account_info(),
+                to: ctx.accounts.bond.to_account_info(),
+            },
+        ),
+        bond,
+    )?;
+
+    proposal.disputer = Some(ctx.accounts.disputer.key());
+    proposal.disputed_at = Some(clock.unix_timestamp);
+    market.proposal = Some(proposal);
+    market.status = MarketStatus::Disputed;
+
+    msg!(
+        "Market {}: {} disputed the {} proposal",
+        market.market_id,
+        ctx.accounts.disputer.key(),
+        if proposal.outcome { "YES" } else { "NO" }
+    );
+
+    emit_cpi!(OutcomeDisputed {
+        version: EVENT_VERSION,
+        market: market.key(),
+        market_id: market.market_id,
+        proposer: proposal.proposer,
+        disputer: ctx.accounts.disputer.key(),
+        bond,
+    });
+    Ok(())
+}
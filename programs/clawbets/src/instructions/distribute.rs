@@ -0,0 +1,244 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::state::*;
+use crate::errors::ClawBetsError;
+use crate::events::*;
+use crate::{collateral, payout};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct Distribute<'info> {
+    #[account(mut)]
+    pub cranker: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"market", market.market_id.to_le_bytes().as_ref()],
+        bump = market.bump,
+    )]
+    pub market: Account<'info, Market>,
+
+    #[account(
+        mut,
+        seeds = [b"protocol"],
+        bump = protocol.bump,
+    )]
+    pub protocol: Account<'info, Protocol>,
+
+    /// CHECK: Vault PDA holding escrowed SOL
+    #[account(
+        mut,
+        seeds = [b"vault", market.key().as_ref()],
+        bump = market.vault_bump,
+    )]
+    pub vault: SystemAccount<'info>,
+
+    /// CHECK: Treasury PDA receiving the protocol fee
+    #[account(
+        mut,
+        seeds = [b"treasury"],
+        bump = protocol.treasury_bump,
+    )]
+    pub treasury: SystemAccount<'info>,
+
+    /// Collateral mint (token markets only)
+    pub collateral_mint: Option<InterfaceAccount<'info, Mint>>,
+
+    /// Token vault owned by the vault PDA (token markets only)
+    #[account(
+        mut,
+        seeds = [b"token_vault", market.key().as_ref()],
+        bump,
+    )]
+    pub vault_token: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Treasury's associated token account (token markets only)
+    #[account(
+        mut,
+        associated_token::mint = collateral_mint,
+        associated_token::authority = treasury,
+        associated_token::token_program = token_program,
+    )]
+    pub treasury_token: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Cranker's token account receiving the crank reward (token markets only)
+    #[account(
+        mut,
+        token::mint = collateral_mint,
+        token::authority = cranker,
+    )]
+    pub cranker_token: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Anyone can pay out a batch of winning bets on a resolved market. Remaining
+/// accounts are `(bet, bettor, reputation)` groups, all writable, with the
+/// bettor's token account appended on token markets. Each winner is paid and
+/// credited exactly as by `claim_winnings` and their bet account closed; groups
+/// whose bet is already closed or didn't win are skipped. The cranker earns
+/// `crank_reward_bps` of the protocol fee.
+pub fn handler<'info>(ctx: Context<'_, '_, 'info, 'info, Distribute<'info>>) -> Result<()> {
+    let market_key = ctx.accounts.market.key();
+    let market = &mut ctx.accounts.market;
+    let protocol = &mut ctx.accounts.protocol;
+    let now = Clock::get()?.unix_timestamp;
+
+    // Validations
+    protocol.require_unpaused(Protocol::PAUSE_CLAIM)?;
+    market.require_unfrozen()?;
+    require!(market.status == MarketStatus::Resolved, ClawBetsError::MarketNotResolved);
+
+    let token = collateral::for_market(market, &ctx.accounts.collateral_mint, &ctx.accounts.token_program)?;
+    let is_native = token.is_none();
+    let group_len = if is_native { 3 } else { 4 };
+    require!(
+        ctx.remaining_accounts.len().is_multiple_of(group_len),
+        ClawBetsError::InvalidRemainingAccounts
+    );
+
+    // SOL goes to `to`; token markets pay `to_token` from the token vault instead
+    let vault_seeds: &[&[u8]] = &[b"vault", market_key.as_ref(), &[market.vault_bump]];
+    let pay_from_vault = |to: AccountInfo<'info>,
+                          to_token: Option<&InterfaceAccount<'info, TokenAccount>>,
+                          amount: u64|
+     -> Result<()> {
+        if amount == 0 {
+            return Ok(());
+        }
+        match &token {
+            None => system_program::transfer(
+                CpiContext::new_with_signer(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.vault.to_account_info(),
+                        to,
+                    },
+                    &[vault_seeds],
+                ),
+                amount,
+            ),
+            Some(token) => token.transfer(
+                collateral::required(&ctx.accounts.vault_token)?,
+                to_token.ok_or(ClawBetsError::MissingCollateralAccounts)?,
+                ctx.accounts.vault.to_account_info(),
+                &[vault_seeds],
+                amount,
+            ),
+        }
+    };
+
+    let (mut paid, mut skipped) = (0u32, 0u32);
+    let (mut total_winnings, mut total_fee) = (0u64, 0u64);
+    for group in ctx.remaining_accounts.chunks(group_len) {
+        let (bet_info, bettor, rep_info) = (&group[0], &group[1], &group[2]);
+
+        // Claimed bets are closed, including ones paid earlier in this batch
+        if bet_info.data_is_empty() {
+            skipped += 1;
+            continue;
+        }
+        let bet: Account<'info, Bet> = Account::try_from(bet_info)?;
+        let mut rep: Account<'info, AgentReputation> = Account::try_from(rep_info)?;
+        require!(
+            bet.market == market_key
+                && bet.bettor == bettor.key()
+                && rep.agent == bettor.key()
+                && bet_info.is_writable
+                && bettor.is_writable
+                && rep_info.is_writable,
+            ClawBetsError::InvalidRemainingAccounts
+        );
+        let bettor_token: Option<InterfaceAccount<'info, TokenAccount>> = match group.get(3) {
+            Some(info) => {
+                let account = InterfaceAccount::<TokenAccount>::try_from(info)?;
+                require!(
+                    Some(account.mint) == market.collateral_mint
+                        && account.owner == bettor.key()
+                        && info.is_writable,
+                    ClawBetsError::InvalidRemainingAccounts
+                );
+                Some(account)
+            }
+            None => None,
+        };
+        if !market.is_winning_bet(&bet)? {
+            skipped += 1;
+            continue;
+        }
+
+        let payout = market.winning_payout(&bet, protocol.fee_bps)?;
+        let winnings = payout.winnings()?;
+        pay_from_vault(bettor.clone(), bettor_token.as_ref(), winnings)?;
+
+        // Creator fee stays in the vault until claim_creator_fees
+        market.creator_fees = market
+            .creator_fees
+            .checked_add(payout.creator_fee)
+            .ok_or(ClawBetsError::Overflow)?;
+        market.record_settled_bet()?;
+
+        rep.record_claim(&bet, &payout, is_native, now)?;
+        rep.exit(&crate::ID)?;
+        bet.close(bettor.clone())?;
+
+        paid += 1;
+        total_winnings = total_winnings.checked_add(winnings).ok_or(ClawBetsError::Overflow)?;
+        total_fee = total_fee.checked_add(payout.protocol_fee).ok_or(ClawBetsError::Overflow)?;
+
+        emit_cpi!(WinningsClaimed {
+            version: EVENT_VERSION,
+            market: market_key,
+            bettor: bet.bettor,
+            stake: payout.stake,
+            winnings,
+            protocol_fee: payout.protocol_fee,
+            creator_fee: payout.creator_fee,
+            creator_fees: market.creator_fees,
+        });
+    }
+
+    // The cranker's reward comes out of the protocol fee, never the winners' payouts
+    let crank_reward = payout::bps_of(total_fee, protocol.crank_reward_bps)?;
+    let treasury_fee = total_fee - crank_reward;
+    pay_from_vault(
+        ctx.accounts.cranker.to_account_info(),
+        ctx.accounts.cranker_token.as_ref(),
+        crank_reward,
+    )?;
+    pay_from_vault(
+        ctx.accounts.treasury.to_account_info(),
+        ctx.accounts.treasury_token.as_ref(),
+        treasury_fee,
+    )?;
+    if is_native {
+        protocol.fees_collected = protocol
+            .fees_collected
+            .checked_add(treasury_fee)
+            .ok_or(ClawBetsError::Overflow)?;
+    }
+
+    msg!(
+        "Distributed {} {} to {} winners on market {} ({} skipped, crank reward {})",
+        total_winnings,
+        if is_native { "lamports" } else { "tokens" },
+        paid,
+        market.market_id,
+        skipped,
+        crank_reward
+    );
+
+    emit_cpi!(WinningsDistributed {
+        version: EVENT_VERSION,
+        market: market_key,
+        cranker: ctx.accounts.cranker.key(),
+        paid,
+        skipped,
+        winnings: total_winnings,
+        crank_reward,
+    });
+    Ok(())
+}
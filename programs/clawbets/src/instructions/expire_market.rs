@@ -1,7 +1,10 @@
 use anchor_lang::prelude::*;
+use anchor_lang::system_program;
 use crate::state::*;
 use crate::errors::ClawBetsError;
+use crate::events::*;
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct ExpireMarket<'info> {
     #[account(mut)]
@@ -13,28 +16,93 @@ pub struct ExpireMarket<'info> {
         bump = market.bump,
     )]
     pub market: Account<'info, Market>,
+
+    /// CHECK: Bond escrow PDA (pending optimistic markets only)
+    #[account(
+        mut,
+        seeds = [b"bond", market.key().as_ref()],
+        bump,
+    )]
+    pub bond: Option<SystemAccount<'info>>,
+
+    /// CHECK: Proposer — receives their bond back (pending optimistic markets only)
+    #[account(mut)]
+    pub proposer: Option<SystemAccount<'info>>,
+
+    /// CHECK: Disputer — receives their bond back (disputed markets only)
+    #[account(mut)]
+    pub disputer: Option<SystemAccount<'info>>,
+
+    pub system_program: Program<'info, System>,
 }
 
 /// Anyone can call this after the resolution deadline to mark a market as expired.
-/// This enables bettors to reclaim their funds.
+/// This enables bettors to reclaim their funds. A pending optimistic market expires
+/// instead once its arbitration deadline passes, and its bonds go back to the
+/// proposer and disputer.
 pub fn handler(ctx: Context<ExpireMarket>) -> Result<()> {
+    let market_key = ctx.accounts.market.key();
     let market = &mut ctx.accounts.market;
     let clock = Clock::get()?;
 
-    // Must be past resolution deadline
-    require!(
-        clock.unix_timestamp > market.resolution_deadline,
-        ClawBetsError::MarketNotReady
-    );
+    let mut bonds_returned = 0u64;
+    if let Some(deadline) = market.arbitration_deadline()? {
+        // Proposed or disputed, and never settled
+        require!(clock.unix_timestamp > deadline, ClawBetsError::MarketNotReady);
 
-    // Must not already be resolved or cancelled
-    require!(
-        market.status == MarketStatus::Open || market.status == MarketStatus::Closed,
-        ClawBetsError::MarketNotOpen
-    );
+        let (bond, _) = market.optimistic_terms().ok_or(ClawBetsError::InvalidMarketKind)?;
+        let proposal = market.proposal.ok_or(ClawBetsError::NoPendingProposal)?;
+        let bond_seeds: &[&[u8]] = &[b"bond", market_key.as_ref(), &[proposal.bond_bump]];
+        let escrow = ctx.accounts.bond.as_ref().ok_or(ClawBetsError::ProposalAccountMismatch)?;
+        let posters = [
+            (Some(proposal.proposer), &ctx.accounts.proposer),
+            (proposal.disputer, &ctx.accounts.disputer),
+        ];
+        for (poster, account) in posters {
+            let Some(poster) = poster else {
+                continue;
+            };
+            let account = account
+                .as_ref()
+                .filter(|account| account.key() == poster)
+                .ok_or(ClawBetsError::ProposalAccountMismatch)?;
+            system_program::transfer(
+                CpiContext::new_with_signer(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: escrow.to_account_info(),
+                        to: account.to_account_info(),
+                    },
+                    &[bond_seeds],
+                ),
+                bond,
+            )?;
+            bonds_returned = bonds_returned.checked_add(bond).ok_or(ClawBetsError::Overflow)?;
+        }
+    } else {
+        // Must be past resolution deadline
+        require!(
+            clock.unix_timestamp > market.resolution_deadline,
+            ClawBetsError::MarketNotReady
+        );
+
+        // Must not already be resolved or cancelled
+        require!(
+            market.status == MarketStatus::Open || market.status == MarketStatus::Closed,
+            ClawBetsError::MarketNotOpen
+        );
+    }
 
     market.status = MarketStatus::Expired;
 
     msg!("Market {} expired — bettors can now reclaim funds", market.market_id);
+
+    emit_cpi!(MarketExpired {
+        version: EVENT_VERSION,
+        market: market.key(),
+        market_id: market.market_id,
+        expired_at: clock.unix_timestamp,
+        bonds_returned,
+    });
     Ok(())
 }
@@ -0,0 +1,197 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::state::*;
+use crate::errors::ClawBetsError;
+use crate::events::*;
+use crate::collateral;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct FinalizeMarket<'info> {
+    pub caller: Signer<'info>,
+
+    /// CHECK: Market creator — receives unclaimed creator fees, unredeemed AMM
+    /// liquidity and the market's rent
+    #[account(mut)]
+    pub creator: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"market", market.market_id.to_le_bytes().as_ref()],
+        bump = market.bump,
+        has_one = creator @ ClawBetsError::UnauthorizedCreator,
+        close = creator,
+    )]
+    pub market: Account<'info, Market>,
+
+    #[account(
+        seeds = [b"protocol"],
+        bump = protocol.bump,
+    )]
+    pub protocol: Account<'info, Protocol>,
+
+    /// Creator's reputation — credited with the unclaimed creator fees paid out here
+    #[account(
+        mut,
+        seeds = [b"reputation", creator.key().as_ref()],
+        bump = reputation.bump,
+    )]
+    pub reputation: Account<'info, AgentReputation>,
+
+    /// CHECK: Vault PDA holding escrowed SOL
+    #[account(
+        mut,
+        seeds = [b"vault", market.key().as_ref()],
+        bump = market.vault_bump,
+    )]
+    pub vault: SystemAccount<'info>,
+
+    /// CHECK: Treasury PDA receiving the rounding dust
+    #[account(
+        mut,
+        seeds = [b"treasury"],
+        bump = protocol.treasury_bump,
+    )]
+    pub treasury: SystemAccount<'info>,
+
+    /// Collateral mint (token markets only)
+    pub collateral_mint: Option<InterfaceAccount<'info, Mint>>,
+
+    /// Token vault owned by the vault PDA (token markets only) — closed here
+    #[account(
+        mut,
+        seeds = [b"token_vault", market.key().as_ref()],
+        bump,
+    )]
+    pub vault_token: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Creator's token account (token markets only)
+    #[account(
+        mut,
+        token::mint = collateral_mint,
+        token::authority = creator,
+    )]
+    pub creator_token: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Treasury's associated token account (token markets only)
+    #[account(
+        mut,
+        associated_token::mint = collateral_mint,
+        associated_token::authority = treasury,
+        associated_token::token_program = token_program,
+    )]
+    pub treasury_token: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Anyone can call this once every bet on a finished market is settled. Unclaimed
+/// creator fees and AMM pool liquidity go to the creator, rounding dust to the treasury, and the drained
+/// vault and the market account are closed with their rent returned to the creator.
+pub fn handler(ctx: Context<FinalizeMarket>) -> Result<()> {
+    let market_key = ctx.accounts.market.key();
+    let market = &ctx.accounts.market;
+
+    // Validations
+    require!(
+        matches!(
+            market.status,
+            MarketStatus::Resolved | MarketStatus::Cancelled | MarketStatus::Void | MarketStatus::Expired
+        ),
+        ClawBetsError::MarketNotFinalizable
+    );
+    require!(market.is_fully_settled()?, ClawBetsError::UnsettledBets);
+
+    let vault_seeds: &[&[u8]] = &[b"vault", market_key.as_ref(), &[market.vault_bump]];
+    let owed_to_creator = market
+        .creator_fees
+        .checked_add(market.lp_claim())
+        .ok_or(ClawBetsError::Overflow)?;
+    let (creator_amount, dust) = match collateral::for_market(
+        market,
+        &ctx.accounts.collateral_mint,
+        &ctx.accounts.token_program,
+    )? {
+        None => {
+            let balance = ctx.accounts.vault.lamports();
+            let creator_amount = owed_to_creator.min(balance);
+            let dust = balance - creator_amount;
+            for (to, amount) in [
+                (ctx.accounts.creator.to_account_info(), creator_amount),
+                (ctx.accounts.treasury.to_account_info(), dust),
+            ] {
+                if amount > 0 {
+                    system_program::transfer(
+                        CpiContext::new_with_signer(
+                            ctx.accounts.system_program.to_account_info(),
+                            system_program::Transfer {
+                                from: ctx.accounts.vault.to_account_info(),
+                                to,
+                            },
+                            &[vault_seeds],
+                        ),
+                        amount,
+                    )?;
+                }
+            }
+            (creator_amount, dust)
+        }
+        Some(token) => {
+            let vault_token = collateral::required(&ctx.accounts.vault_token)?;
+            let creator_amount = owed_to_creator.min(vault_token.amount);
+            let dust = vault_token.amount - creator_amount;
+            if creator_amount > 0 {
+                token.transfer(
+                    vault_token,
+                    collateral::required(&ctx.accounts.creator_token)?,
+                    ctx.accounts.vault.to_account_info(),
+                    &[vault_seeds],
+                    creator_amount,
+                )?;
+            }
+            if dust > 0 {
+                token.transfer(
+                    vault_token,
+                    collateral::required(&ctx.accounts.treasury_token)?,
+                    ctx.accounts.vault.to_account_info(),
+                    &[vault_seeds],
+                    dust,
+                )?;
+            }
+            token.close(
+                vault_token,
+                ctx.accounts.creator.to_account_info(),
+                ctx.accounts.vault.to_account_info(),
+                &[vault_seeds],
+            )?;
+            (creator_amount, dust)
+        }
+    };
+
+    // Same reputation credit claim_creator_fees gives, for fees paid out here instead
+    if market.collateral_mint.is_none() {
+        let fees = market.creator_fees.min(creator_amount);
+        let rep = &mut ctx.accounts.reputation;
+        rep.creator_fees_earned = rep.creator_fees_earned.checked_add(fees).ok_or(ClawBetsError::Overflow)?;
+    }
+
+    msg!(
+        "Market {} finalized: {} to the creator, {} dust swept",
+        market.market_id,
+        creator_amount,
+        dust
+    );
+
+    emit_cpi!(MarketFinalized {
+        version: EVENT_VERSION,
+        market: market_key,
+        market_id: market.market_id,
+        creator_amount,
+        dust,
+        finalized_at: Clock::get()?.unix_timestamp,
+    });
+    Ok(())
+}
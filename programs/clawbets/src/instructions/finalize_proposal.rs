@@ -0,0 +1,102 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use crate::state::*;
+use crate::errors::ClawBetsError;
+use crate::events::*;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct FinalizeProposal<'info> {
+    pub caller: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"market", market.market_id.to_le_bytes().as_ref()],
+        bump = market.bump,
+    )]
+    pub market: Account<'info, Market>,
+
+    #[account(
+        seeds = [b"protocol"],
+        bump = protocol.bump,
+    )]
+    pub protocol: Account<'info, Protocol>,
+
+    /// CHECK: Bond escrow PDA for this market
+    #[account(
+        mut,
+        seeds = [b"bond", market.key().as_ref()],
+        bump,
+    )]
+    pub bond: SystemAccount<'info>,
+
+    /// CHECK: Proposer — receives their bond back
+    #[account(mut)]
+    pub proposer: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Anyone can finalize an undisputed proposal once the challenge period is over.
+/// The market resolves to the proposed outcome and the proposer's bond is returned.
+pub fn handler(ctx: Context<FinalizeProposal>) -> Result<()> {
+    let market_key = ctx.accounts.market.key();
+    let market = &mut ctx.accounts.market;
+    let clock = Clock::get()?;
+
+    // Validations
+    ctx.accounts.protocol.require_unpaused(Protocol::PAUSE_RESOLVE)?;
+    market.require_unfrozen()?;
+    let (bond, challenge_period) = market
+        .optimistic_terms()
+        .ok_or(ClawBetsError::InvalidMarketKind)?;
+    require!(market.status == MarketStatus::Proposed, ClawBetsError::NoPendingProposal);
+    let proposal = market.proposal.ok_or(ClawBetsError::NoPendingProposal)?;
+    require!(
+        ctx.accounts.proposer.key() == proposal.proposer,
+        ClawBetsError::ProposalAccountMismatch
+    );
+    let challenge_ends_at = proposal
+        .proposed_at
+        .checked_add(challenge_period)
+        .ok_or(ClawBetsError::Overflow)?;
+    require!(
+        clock.unix_timestamp >= challenge_ends_at,
+        ClawBetsError::ChallengePeriodActive
+    );
+
+    let bond_seeds: &[&[u8]] = &[b"bond", market_key.as_ref(), &[proposal.bond_bump]];
+    system_program::transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.bond.to_account_info(),
+                to: ctx.accounts.proposer.to_account_info(),
+            },
+            &[bond_seeds],
+        ),
+        bond,
+    )?;
+
+    market.status = MarketStatus::Resolved;
+    market.outcome = Some(proposal.outcome);
+    market.resolved_at = Some(clock.unix_timestamp);
+
+    msg!(
+        "Market {} resolved by undisputed proposal: {}",
+        market.market_id,
+        if proposal.outcome { "YES wins" } else { "NO wins" }
+    );
+
+    emit_cpi!(ProposalResolved {
+        version: EVENT_VERSION,
+        market: market.key(),
+        market_id: market.market_id,
+        outcome: proposal.outcome,
+        disputed: false,
+        bond_recipient: proposal.proposer,
+        bond_paid: bond,
+        resolved_at: clock.unix_timestamp,
+    });
+    Ok(())
+}
@@ -0,0 +1,119 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use crate::state::*;
+use crate::errors::ClawBetsError;
+use crate::events::*;
+use crate::oracle::Settlement;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct FinalizeResolution<'info> {
+    pub caller: Signer<'info>,
+
+    #[account(
+        seeds = [b"protocol"],
+        bump = protocol.bump,
+    )]
+    pub protocol: Account<'info, Protocol>,
+
+    #[account(
+        mut,
+        seeds = [b"market", market.market_id.to_le_bytes().as_ref()],
+        bump = market.bump,
+    )]
+    pub market: Account<'info, Market>,
+
+    /// CHECK: Bond escrow PDA for this market
+    #[account(
+        mut,
+        seeds = [b"bond", market.key().as_ref()],
+        bump,
+    )]
+    pub bond: SystemAccount<'info>,
+
+    /// CHECK: Challenger — receives their bond back if the challenge stood
+    #[account(mut)]
+    pub challenger: Option<SystemAccount<'info>>,
+
+    /// CHECK: Treasury PDA — receives the bond of an overturned challenge
+    #[account(
+        mut,
+        seeds = [b"treasury"],
+        bump = protocol.treasury_bump,
+    )]
+    pub treasury: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Anyone can finalize an oracle resolution once its dispute period is over,
+/// opening claims (or refunds if it settled void) and settling any challenge bond.
+pub fn handler(ctx: Context<FinalizeResolution>) -> Result<()> {
+    let market_key = ctx.accounts.market.key();
+    let market = &mut ctx.accounts.market;
+    let clock = Clock::get()?;
+
+    // Validations
+    ctx.accounts.protocol.require_unpaused(Protocol::PAUSE_RESOLVE)?;
+    market.require_unfrozen()?;
+    require!(market.status == MarketStatus::Resolving, ClawBetsError::NotResolving);
+    let finalizes_at = market.finalizes_at.ok_or(ClawBetsError::NotResolving)?;
+    require!(clock.unix_timestamp >= finalizes_at, ClawBetsError::DisputePeriodActive);
+
+    // A challenge the market settled as gets its bond back; any other forfeits it
+    let settlement = market.settlement();
+    let bond_recipient = match (market.challenge, market.challenge_upheld()) {
+        (Some(challenge), Some(upheld)) => {
+            let recipient = if upheld {
+                let challenger = ctx
+                    .accounts
+                    .challenger
+                    .as_ref()
+                    .ok_or(ClawBetsError::ChallengerMismatch)?;
+                require!(
+                    challenger.key() == challenge.challenger,
+                    ClawBetsError::ChallengerMismatch
+                );
+                challenger.to_account_info()
+            } else {
+                ctx.accounts.treasury.to_account_info()
+            };
+            let bond_seeds: &[&[u8]] = &[b"bond", market_key.as_ref(), &[challenge.bond_bump]];
+            system_program::transfer(
+                CpiContext::new_with_signer(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.bond.to_account_info(),
+                        to: recipient.clone(),
+                    },
+                    &[bond_seeds],
+                ),
+                challenge.bond,
+            )?;
+            Some(recipient.key())
+        }
+        _ => None,
+    };
+
+    let void = settlement == Settlement::Void;
+    market.status = if void { MarketStatus::Void } else { MarketStatus::Resolved };
+
+    msg!(
+        "Market {} resolution final: {}",
+        market.market_id,
+        settlement.describe()
+    );
+
+    emit_cpi!(ResolutionFinalized {
+        version: EVENT_VERSION,
+        market: market.key(),
+        market_id: market.market_id,
+        void,
+        outcome: market.outcome,
+        winning_bucket: market.winning_bucket,
+        scalar_offset: market.scalar_offset,
+        bond_recipient,
+        finalized_at: clock.unix_timestamp,
+    });
+    Ok(())
+}
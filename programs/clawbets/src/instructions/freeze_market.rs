@@ -0,0 +1,40 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::errors::ClawBetsError;
+use crate::events::*;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct FreezeMarket<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [b"protocol"],
+        bump = protocol.bump,
+        has_one = admin @ ClawBetsError::UnauthorizedAdmin,
+    )]
+    pub protocol: Account<'info, Protocol>,
+
+    #[account(
+        mut,
+        seeds = [b"market", market.market_id.to_le_bytes().as_ref()],
+        bump = market.bump,
+    )]
+    pub market: Account<'info, Market>,
+}
+
+/// Freeze or unfreeze a single market. Bettors can still withdraw and reclaim.
+pub fn handler(ctx: Context<FreezeMarket>, frozen: bool) -> Result<()> {
+    let market = &mut ctx.accounts.market;
+    market.frozen = frozen;
+
+    msg!("Market {} frozen: {}", market.market_id, frozen);
+
+    emit_cpi!(MarketFreezeUpdated {
+        version: EVENT_VERSION,
+        market: market.key(),
+        market_id: market.market_id,
+        frozen,
+    });
+    Ok(())
+}
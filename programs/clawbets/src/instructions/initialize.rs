@@ -1,6 +1,9 @@
 use anchor_lang::prelude::*;
+use anchor_lang::system_program;
 use crate::state::Protocol;
+use crate::events::*;
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct Initialize<'info> {
     #[account(mut)]
@@ -15,14 +18,57 @@ pub struct Initialize<'info> {
     )]
     pub protocol: Account<'info, Protocol>,
 
+    /// CHECK: Treasury PDA collecting protocol fees
+    #[account(
+        mut,
+        seeds = [b"treasury"],
+        bump,
+    )]
+    pub treasury: SystemAccount<'info>,
+
     pub system_program: Program<'info, System>,
 }
 
 pub fn handler(ctx: Context<Initialize>) -> Result<()> {
+    // Fund the treasury up to rent exemption so small fee deposits are always accepted
+    let rent_exempt = Rent::get()?.minimum_balance(0);
+    let top_up = rent_exempt.saturating_sub(ctx.accounts.treasury.lamports());
+    if top_up > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.admin.to_account_info(),
+                    to: ctx.accounts.treasury.to_account_info(),
+                },
+            ),
+            top_up,
+        )?;
+    }
+
     let protocol = &mut ctx.accounts.protocol;
     protocol.admin = ctx.accounts.admin.key();
     protocol.market_count = 0;
     protocol.total_volume = 0;
     protocol.bump = ctx.bumps.protocol;
+    protocol.fee_bps = 0;
+    protocol.fees_collected = 0;
+    protocol.treasury_bump = ctx.bumps.treasury;
+    protocol.dispute_period = Protocol::DEFAULT_DISPUTE_PERIOD;
+    protocol.challenge_bond = Protocol::DEFAULT_CHALLENGE_BOND;
+    protocol.pending_admin = None;
+    // The admin holds every role until it delegates them with set_role
+    protocol.fee_manager = protocol.admin;
+    protocol.feed_curator = protocol.admin;
+    protocol.pauser = protocol.admin;
+    protocol.dispute_arbiter = protocol.admin;
+    protocol.paused = 0;
+    protocol.crank_reward_bps = 0;
+
+    emit_cpi!(ProtocolInitialized {
+        version: EVENT_VERSION,
+        admin: protocol.admin,
+        treasury: ctx.accounts.treasury.key(),
+    });
     Ok(())
 }
@@ -0,0 +1,95 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::errors::ClawBetsError;
+use crate::events::*;
+use crate::migration::{self, BetV0};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct MigrateBet<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// Market the bet belongs to, already migrated with `migrate_market`
+    #[account(
+        mut,
+        seeds = [b"market", market.market_id.to_le_bytes().as_ref()],
+        bump = market.bump,
+    )]
+    pub market: Account<'info, Market>,
+
+    /// CHECK: Bet PDA on its original layout, checked by `migration::load_legacy`
+    /// and against its seeds in the handler
+    #[account(mut)]
+    pub bet: UncheckedAccount<'info>,
+
+    /// CHECK: Bettor — receives the rent of a bet that was already claimed
+    #[account(mut)]
+    pub bettor: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Anyone can upgrade a bet placed before the current layout. A bet whose winnings
+/// were already claimed is closed instead, returning its rent to the bettor, and
+/// counts as settled so the market can still be finalized.
+pub fn handler(ctx: Context<MigrateBet>) -> Result<()> {
+    let legacy: BetV0 = migration::load_legacy::<Bet, _>(&ctx.accounts.bet)?;
+    let market_key = ctx.accounts.market.key();
+    let expected = Pubkey::create_program_address(
+        &[b"bet", market_key.as_ref(), legacy.bettor.as_ref(), &[legacy.bump]],
+        &crate::ID,
+    )
+    .map_err(|_| ClawBetsError::NotMigratable)?;
+    require_keys_eq!(ctx.accounts.bet.key(), expected, ClawBetsError::NotMigratable);
+    require!(
+        legacy.market == market_key && legacy.bettor == ctx.accounts.bettor.key(),
+        ClawBetsError::NotMigratable
+    );
+
+    let bet_info = ctx.accounts.bet.to_account_info();
+    if legacy.claimed {
+        ctx.accounts.market.record_settled_bet()?;
+        let bettor = ctx.accounts.bettor.to_account_info();
+        let rent = bet_info.lamports();
+        **bettor.try_borrow_mut_lamports()? = bettor
+            .lamports()
+            .checked_add(rent)
+            .ok_or(ClawBetsError::Overflow)?;
+        **bet_info.try_borrow_mut_lamports()? = 0;
+        bet_info.assign(&System::id());
+        bet_info.resize(0)?;
+    } else {
+        let bet = Bet {
+            bettor: legacy.bettor,
+            market: legacy.market,
+            amount: legacy.amount,
+            position: legacy.position,
+            placed_at: legacy.placed_at,
+            bump: legacy.bump,
+            last_deposit_at: legacy.placed_at,
+            ..Default::default()
+        };
+        migration::store_migrated(
+            &bet_info,
+            &ctx.accounts.payer,
+            &ctx.accounts.system_program,
+            &bet,
+        )?;
+    }
+
+    msg!(
+        "Bet of {} on market {} migrated{}",
+        legacy.bettor,
+        ctx.accounts.market.market_id,
+        if legacy.claimed { " and closed as claimed" } else { "" }
+    );
+
+    emit_cpi!(BetMigrated {
+        version: EVENT_VERSION,
+        market: market_key,
+        bettor: legacy.bettor,
+        closed: legacy.claimed,
+    });
+    Ok(())
+}
@@ -0,0 +1,89 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::errors::ClawBetsError;
+use crate::events::*;
+use crate::migration::{self, MarketV0};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct MigrateMarket<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: Market PDA on its original layout, checked by `migration::load_legacy`
+    /// and against its seeds in the handler
+    #[account(mut)]
+    pub market: UncheckedAccount<'info>,
+
+    /// Whitelist entry for the market's feed, which supplies its exponent and staleness
+    #[account(
+        seeds = [b"feed", feed.feed_id.as_ref()],
+        bump = feed.bump,
+    )]
+    pub feed: Account<'info, FeedConfig>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Anyone can upgrade a market created before the current layout, paying for the
+/// extra rent. It becomes a binary SOL market priced in its feed's exponent, with
+/// no fees and no confidence check, so existing bets settle as they always would
+/// have. Its bets are migrated afterwards with `migrate_bet`.
+pub fn handler(ctx: Context<MigrateMarket>) -> Result<()> {
+    let legacy: MarketV0 = migration::load_legacy::<Market, _>(&ctx.accounts.market)?;
+    let expected = Pubkey::create_program_address(
+        &[b"market", legacy.market_id.to_le_bytes().as_ref(), &[legacy.bump]],
+        &crate::ID,
+    )
+    .map_err(|_| ClawBetsError::NotMigratable)?;
+    require_keys_eq!(ctx.accounts.market.key(), expected, ClawBetsError::NotMigratable);
+    let feed = &ctx.accounts.feed;
+    require!(feed.feed_id == legacy.feed_id, ClawBetsError::FeedNotWhitelisted);
+
+    let market = Market {
+        creator: legacy.creator,
+        market_id: legacy.market_id,
+        title: legacy.title,
+        description: legacy.description,
+        feed_id: legacy.feed_id,
+        target_price: legacy.target_price,
+        target_above: legacy.target_above,
+        deadline: legacy.deadline,
+        resolution_deadline: legacy.resolution_deadline,
+        min_bet: legacy.min_bet,
+        max_bet: legacy.max_bet,
+        total_yes: legacy.total_yes,
+        total_no: legacy.total_no,
+        yes_count: legacy.yes_count,
+        no_count: legacy.no_count,
+        status: legacy.status,
+        outcome: legacy.outcome,
+        resolved_price: legacy.resolved_price,
+        resolved_at: legacy.resolved_at,
+        created_at: legacy.created_at,
+        bump: legacy.bump,
+        vault_bump: legacy.vault_bump,
+        expected_exponent: feed.exponent,
+        resolved_exponent: legacy.resolved_price.map(|_| feed.exponent),
+        price_window: feed.max_staleness,
+        max_conf_bps: 10_000,
+        max_staleness: feed.max_staleness,
+        ..Default::default()
+    };
+    migration::store_migrated(
+        &ctx.accounts.market,
+        &ctx.accounts.payer,
+        &ctx.accounts.system_program,
+        &market,
+    )?;
+
+    msg!("Market {} migrated to the current layout", market.market_id);
+
+    emit_cpi!(MarketMigrated {
+        version: EVENT_VERSION,
+        market: ctx.accounts.market.key(),
+        market_id: market.market_id,
+        expected_exponent: market.expected_exponent,
+    });
+    Ok(())
+}
@@ -0,0 +1,90 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use crate::state::Protocol;
+use crate::errors::ClawBetsError;
+use crate::events::*;
+use crate::migration::{self, ProtocolV0};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct MigrateProtocol<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    /// CHECK: Protocol PDA on its original layout, checked by `migration::load_legacy`
+    #[account(
+        mut,
+        seeds = [b"protocol"],
+        bump,
+    )]
+    pub protocol: UncheckedAccount<'info>,
+
+    /// CHECK: Treasury PDA collecting protocol fees
+    #[account(
+        mut,
+        seeds = [b"treasury"],
+        bump,
+    )]
+    pub treasury: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Admin-only upgrade of a protocol account created before fees, disputes and roles.
+/// The account is grown to the current layout and the new fields take the same
+/// defaults as `initialize`, with the admin holding every role.
+pub fn handler(ctx: Context<MigrateProtocol>) -> Result<()> {
+    let legacy: ProtocolV0 = migration::load_legacy::<Protocol, _>(&ctx.accounts.protocol)?;
+    require_keys_eq!(ctx.accounts.admin.key(), legacy.admin, ClawBetsError::UnauthorizedAdmin);
+
+    // Fund the treasury up to rent exemption, as initialize does
+    let rent_exempt = Rent::get()?.minimum_balance(0);
+    let top_up = rent_exempt.saturating_sub(ctx.accounts.treasury.lamports());
+    if top_up > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.admin.to_account_info(),
+                    to: ctx.accounts.treasury.to_account_info(),
+                },
+            ),
+            top_up,
+        )?;
+    }
+
+    let protocol = Protocol {
+        admin: legacy.admin,
+        market_count: legacy.market_count,
+        total_volume: legacy.total_volume,
+        bump: legacy.bump,
+        fee_bps: 0,
+        fees_collected: 0,
+        treasury_bump: ctx.bumps.treasury,
+        dispute_period: Protocol::DEFAULT_DISPUTE_PERIOD,
+        challenge_bond: Protocol::DEFAULT_CHALLENGE_BOND,
+        pending_admin: None,
+        fee_manager: legacy.admin,
+        feed_curator: legacy.admin,
+        pauser: legacy.admin,
+        dispute_arbiter: legacy.admin,
+        paused: 0,
+        crank_reward_bps: 0,
+    };
+    migration::store_migrated(
+        &ctx.accounts.protocol,
+        &ctx.accounts.admin,
+        &ctx.accounts.system_program,
+        &protocol,
+    )?;
+
+    msg!("Protocol migrated to the current layout ({} markets)", protocol.market_count);
+
+    emit_cpi!(ProtocolMigrated {
+        version: EVENT_VERSION,
+        admin: protocol.admin,
+        treasury: ctx.accounts.treasury.key(),
+        market_count: protocol.market_count,
+    });
+    Ok(())
+}
@@ -0,0 +1,61 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::errors::ClawBetsError;
+use crate::events::*;
+use crate::migration::{self, AgentReputationV0};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct MigrateReputation<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: Reputation PDA on its previous layout, checked by `migration::load_legacy`
+    /// and against its seeds in the handler
+    #[account(mut)]
+    pub reputation: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Anyone can upgrade a reputation account created before creator fees were
+/// tracked, paying for the extra rent. `place_bet` and `create_market` can't load
+/// the agent's reputation until this has run.
+pub fn handler(ctx: Context<MigrateReputation>) -> Result<()> {
+    let legacy: AgentReputationV0 = migration::load_legacy::<AgentReputation, _>(&ctx.accounts.reputation)?;
+    let expected = Pubkey::create_program_address(
+        &[b"reputation", legacy.agent.as_ref(), &[legacy.bump]],
+        &crate::ID,
+    )
+    .map_err(|_| ClawBetsError::NotMigratable)?;
+    require_keys_eq!(ctx.accounts.reputation.key(), expected, ClawBetsError::NotMigratable);
+
+    let reputation = AgentReputation {
+        agent: legacy.agent,
+        total_bets: legacy.total_bets,
+        wins: legacy.wins,
+        losses: legacy.losses,
+        total_wagered: legacy.total_wagered,
+        total_won: legacy.total_won,
+        total_lost: legacy.total_lost,
+        markets_created: legacy.markets_created,
+        accuracy_bps: legacy.accuracy_bps,
+        last_active: legacy.last_active,
+        bump: legacy.bump,
+        creator_fees_earned: 0,
+    };
+    migration::store_migrated(
+        &ctx.accounts.reputation,
+        &ctx.accounts.payer,
+        &ctx.accounts.system_program,
+        &reputation,
+    )?;
+
+    msg!("Reputation of {} migrated to the current layout", reputation.agent);
+
+    emit_cpi!(ReputationMigrated {
+        version: EVENT_VERSION,
+        agent: reputation.agent,
+    });
+    Ok(())
+}
@@ -7,3 +7,34 @@ pub mod cancel_market;
 pub mod reclaim_bet;
 pub mod expire_market;
 pub mod close_betting;
+pub mod settle_loss;
+pub mod set_fee;
+pub mod withdraw_fees;
+pub mod claim_creator_fees;
+pub mod withdraw_bet;
+pub mod record_touch;
+pub mod record_observation;
+pub mod snapshot_start;
+pub mod propose_outcome;
+pub mod dispute;
+pub mod finalize_proposal;
+pub mod arbitrate;
+pub mod set_dispute_params;
+pub mod challenge_resolution;
+pub mod overturn_resolution;
+pub mod finalize_resolution;
+pub mod add_feed;
+pub mod update_feed;
+pub mod disable_feed;
+pub mod propose_admin;
+pub mod accept_admin;
+pub mod set_role;
+pub mod set_paused;
+pub mod freeze_market;
+pub mod finalize_market;
+pub mod set_crank_reward;
+pub mod distribute;
+pub mod migrate_protocol;
+pub mod migrate_market;
+pub mod migrate_bet;
+pub mod migrate_reputation;
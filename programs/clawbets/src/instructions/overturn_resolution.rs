@@ -0,0 +1,58 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::errors::ClawBetsError;
+use crate::events::*;
+use crate::oracle::Settlement;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct OverturnResolution<'info> {
+    pub dispute_arbiter: Signer<'info>,
+
+    #[account(
+        seeds = [b"protocol"],
+        bump = protocol.bump,
+        has_one = dispute_arbiter @ ClawBetsError::UnauthorizedRole,
+    )]
+    pub protocol: Account<'info, Protocol>,
+
+    #[account(
+        mut,
+        seeds = [b"market", market.market_id.to_le_bytes().as_ref()],
+        bump = market.bump,
+    )]
+    pub market: Account<'info, Market>,
+}
+
+/// Dispute arbiter's correction of an oracle resolution during its dispute period. A
+/// challenger's bond is returned at finalization only if the market ends up settled as
+/// they claimed, so this is also how a claimed challenge is upheld.
+pub fn handler(ctx: Context<OverturnResolution>, settlement: Settlement) -> Result<()> {
+    let market = &mut ctx.accounts.market;
+    let clock = Clock::get()?;
+
+    // Validations
+    require!(market.status == MarketStatus::Resolving, ClawBetsError::NotResolving);
+    let finalizes_at = market.finalizes_at.ok_or(ClawBetsError::NotResolving)?;
+    require!(clock.unix_timestamp < finalizes_at, ClawBetsError::DisputePeriodOver);
+    market.validate_settlement(settlement)?;
+
+    market.apply_settlement(settlement);
+
+    msg!(
+        "Market {} resolution overturned by the dispute arbiter: now {}",
+        market.market_id,
+        settlement.describe()
+    );
+
+    emit_cpi!(ResolutionOverturned {
+        version: EVENT_VERSION,
+        market: market.key(),
+        market_id: market.market_id,
+        arbiter: ctx.accounts.dispute_arbiter.key(),
+        outcome: market.outcome,
+        winning_bucket: market.winning_bucket,
+        scalar_offset: market.scalar_offset,
+    });
+    Ok(())
+}
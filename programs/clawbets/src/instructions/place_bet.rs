@@ -1,8 +1,12 @@
 use anchor_lang::prelude::*;
 use anchor_lang::system_program;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 use crate::state::*;
 use crate::errors::ClawBetsError;
+use crate::events::*;
+use crate::{amm, collateral};
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct PlaceBet<'info> {
     #[account(mut)]
@@ -15,8 +19,9 @@ pub struct PlaceBet<'info> {
     )]
     pub market: Account<'info, Market>,
 
+    /// Bet account — created on the first bet, topped up on later ones
     #[account(
-        init,
+        init_if_needed,
         payer = bettor,
         space = 8 + Bet::INIT_SPACE,
         seeds = [b"bet", market.key().as_ref(), bettor.key().as_ref()],
@@ -49,53 +54,132 @@ pub struct PlaceBet<'info> {
     )]
     pub protocol: Account<'info, Protocol>,
 
+    /// Collateral mint (token markets only)
+    pub collateral_mint: Option<InterfaceAccount<'info, Mint>>,
+
+    /// Token vault owned by the vault PDA (token markets only)
+    #[account(
+        mut,
+        seeds = [b"token_vault", market.key().as_ref()],
+        bump,
+    )]
+    pub vault_token: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Bettor's token account (token markets only)
+    #[account(
+        mut,
+        token::mint = collateral_mint,
+        token::authority = bettor,
+    )]
+    pub bettor_token: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+
     pub system_program: Program<'info, System>,
 }
 
-pub fn handler(ctx: Context<PlaceBet>, amount: u64, position: bool) -> Result<()> {
+/// `position` picks YES/NO on binary markets (LONG/SHORT on scalar); `bucket` picks the price range on
+/// categorical markets. The other argument is ignored. On AMM markets the bet buys
+/// at least `min_shares_out` shares from the pool; parimutuel markets ignore it.
+pub fn handler(
+    ctx: Context<PlaceBet>,
+    amount: u64,
+    position: bool,
+    bucket: u8,
+    min_shares_out: u64,
+) -> Result<()> {
     let market = &mut ctx.accounts.market;
     let clock = Clock::get()?;
 
     // Validations
-    require!(market.status == MarketStatus::Open, ClawBetsError::MarketNotOpen);
-    require!(clock.unix_timestamp < market.deadline, ClawBetsError::BettingClosed);
+    ctx.accounts.protocol.require_unpaused(Protocol::PAUSE_BET)?;
+    market.require_unfrozen()?;
+    market.require_betting_open(clock.unix_timestamp)?;
     require!(amount >= market.min_bet, ClawBetsError::BetTooSmall);
-    require!(amount <= market.max_bet, ClawBetsError::BetTooLarge);
-
-    // Transfer SOL to vault
-    system_program::transfer(
-        CpiContext::new(
-            ctx.accounts.system_program.to_account_info(),
-            system_program::Transfer {
-                from: ctx.accounts.bettor.to_account_info(),
-                to: ctx.accounts.vault.to_account_info(),
-            },
-        ),
-        amount,
-    )?;
+    // Relative markets take bets only once returns have a fixed starting point
+    if market.feed_b().is_some() {
+        require!(market.start_prices.is_some(), ClawBetsError::StartPricesMissing);
+    }
 
-    // Update market totals
-    if position {
-        market.total_yes = market.total_yes.checked_add(amount).ok_or(ClawBetsError::Overflow)?;
-        market.yes_count = market.yes_count.checked_add(1).ok_or(ClawBetsError::Overflow)?;
+    let (position, bucket) = if market.is_categorical() {
+        require!((bucket as usize) < market.bucket_totals.len(), ClawBetsError::InvalidBucket);
+        (false, bucket)
     } else {
-        market.total_no = market.total_no.checked_add(amount).ok_or(ClawBetsError::Overflow)?;
-        market.no_count = market.no_count.checked_add(1).ok_or(ClawBetsError::Overflow)?;
+        (position, 0)
+    };
+
+    // A top-up adds to the existing position; hedging the other side is rejected
+    let bet = &ctx.accounts.bet;
+    let is_new = bet.bettor == Pubkey::default();
+    if !is_new {
+        require!(
+            bet.position == position && bet.bucket == bucket,
+            ClawBetsError::HedgingNotAllowed
+        );
     }
 
-    // Update protocol volume
+    // max_bet caps the cumulative position, not each deposit
+    let total_amount = bet.amount.checked_add(amount).ok_or(ClawBetsError::Overflow)?;
+    require!(total_amount <= market.max_bet, ClawBetsError::BetTooLarge);
+
+    // AMM markets fix the price at bet time by buying shares from the pool
+    let shares = match market.amm.as_mut() {
+        Some(pool) => {
+            let (yes_reserve, no_reserve, shares) =
+                amm::buy(pool.yes_reserve, pool.no_reserve, position, amount)?;
+            require!(shares >= min_shares_out, ClawBetsError::SlippageExceeded);
+            pool.yes_reserve = yes_reserve;
+            pool.no_reserve = no_reserve;
+            shares
+        }
+        None => 0,
+    };
+
+    // Transfer collateral to vault
+    match collateral::for_market(market, &ctx.accounts.collateral_mint, &ctx.accounts.token_program)? {
+        None => system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.bettor.to_account_info(),
+                    to: ctx.accounts.vault.to_account_info(),
+                },
+            ),
+            amount,
+        )?,
+        Some(token) => token.transfer(
+            collateral::required(&ctx.accounts.bettor_token)?,
+            collateral::required(&ctx.accounts.vault_token)?,
+            ctx.accounts.bettor.to_account_info(),
+            &[],
+            amount,
+        )?,
+    }
+    let is_native = market.collateral_mint.is_none();
+
+    // Update market totals — counts track unique bettors, not deposits
+    market.add_stake(position, bucket, amount, is_new)?;
+
+    // Update protocol volume (SOL only)
     let protocol = &mut ctx.accounts.protocol;
-    protocol.total_volume = protocol.total_volume.checked_add(amount).ok_or(ClawBetsError::Overflow)?;
+    if is_native {
+        protocol.total_volume = protocol.total_volume.checked_add(amount).ok_or(ClawBetsError::Overflow)?;
+    }
 
     // Record bet
     let bet = &mut ctx.accounts.bet;
-    bet.bettor = ctx.accounts.bettor.key();
-    bet.market = market.key();
-    bet.amount = amount;
-    bet.position = position;
-    bet.claimed = false;
-    bet.placed_at = clock.unix_timestamp;
-    bet.bump = ctx.bumps.bet;
+    if is_new {
+        bet.bettor = ctx.accounts.bettor.key();
+        bet.market = market.key();
+        bet.position = position;
+        bet.bucket = bucket;
+        bet.placed_at = clock.unix_timestamp;
+        bet.bump = ctx.bumps.bet;
+        bet.shares = 0;
+    }
+    bet.amount = total_amount;
+    bet.shares = bet.shares.checked_add(shares).ok_or(ClawBetsError::Overflow)?;
+    bet.last_deposit_at = clock.unix_timestamp;
 
     // Update reputation
     let rep = &mut ctx.accounts.reputation;
@@ -103,15 +187,61 @@ pub fn handler(ctx: Context<PlaceBet>, amount: u64, position: bool) -> Result<()
         rep.agent = ctx.accounts.bettor.key();
         rep.bump = ctx.bumps.reputation;
     }
-    rep.total_bets = rep.total_bets.checked_add(1).ok_or(ClawBetsError::Overflow)?;
-    rep.total_wagered = rep.total_wagered.checked_add(amount).ok_or(ClawBetsError::Overflow)?;
+    if is_new {
+        rep.total_bets = rep.total_bets.checked_add(1).ok_or(ClawBetsError::Overflow)?;
+    }
+    if is_native {
+        rep.total_wagered = rep.total_wagered.checked_add(amount).ok_or(ClawBetsError::Overflow)?;
+    }
     rep.last_active = clock.unix_timestamp;
 
+    let side = if market.is_categorical() {
+        format!("bucket {}", bucket)
+    } else {
+        match (market.is_scalar(), position) {
+            (true, true) => "LONG",
+            (true, false) => "SHORT",
+            (false, true) => "YES",
+            (false, false) => "NO",
+        }
+        .to_string()
+    };
     msg!(
-        "Bet placed: {} lamports on {} for market {}",
+        "Bet {}: {} {} on {} for market {}",
+        if is_new { "placed" } else { "topped up" },
         amount,
-        if position { "YES" } else { "NO" },
+        if is_native { "lamports" } else { "tokens" },
+        side,
         market.market_id
     );
+    if let Some(pool) = market.amm {
+        msg!(
+            "Bought {} shares; pool at {} YES / {} NO",
+            shares,
+            pool.yes_reserve,
+            pool.no_reserve
+        );
+    }
+
+    emit_cpi!(BetPlaced {
+        version: EVENT_VERSION,
+        market: market.key(),
+        bettor: bet.bettor,
+        amount,
+        bet_amount: bet.amount,
+        position,
+        bucket,
+        is_new,
+        total_yes: market.total_yes,
+        total_no: market.total_no,
+        yes_count: market.yes_count,
+        no_count: market.no_count,
+        bucket_totals: market.bucket_totals.clone(),
+        bucket_counts: market.bucket_counts.clone(),
+        shares,
+        bet_shares: bet.shares,
+        amm: market.amm,
+        placed_at: clock.unix_timestamp,
+    });
     Ok(())
 }
@@ -0,0 +1,34 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::errors::ClawBetsError;
+use crate::events::*;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ProposeAdmin<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"protocol"],
+        bump = protocol.bump,
+        has_one = admin @ ClawBetsError::UnauthorizedAdmin,
+    )]
+    pub protocol: Account<'info, Protocol>,
+}
+
+/// First step of an admin transfer. The new admin takes over once they call
+/// `accept_admin`; proposing again replaces the pending admin.
+pub fn handler(ctx: Context<ProposeAdmin>, new_admin: Pubkey) -> Result<()> {
+    let protocol = &mut ctx.accounts.protocol;
+    protocol.pending_admin = Some(new_admin);
+
+    msg!("Admin transfer to {} proposed", new_admin);
+
+    emit_cpi!(AdminProposed {
+        version: EVENT_VERSION,
+        admin: protocol.admin,
+        pending_admin: new_admin,
+    });
+    Ok(())
+}
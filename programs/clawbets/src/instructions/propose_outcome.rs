@@ -0,0 +1,105 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use crate::state::*;
+use crate::errors::ClawBetsError;
+use crate::events::*;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ProposeOutcome<'info> {
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"market", market.market_id.to_le_bytes().as_ref()],
+        bump = market.bump,
+    )]
+    pub market: Account<'info, Market>,
+
+    #[account(
+        seeds = [b"protocol"],
+        bump = protocol.bump,
+    )]
+    pub protocol: Account<'info, Protocol>,
+
+    /// CHECK: Bond escrow PDA for this market
+    #[account(
+        mut,
+        seeds = [b"bond", market.key().as_ref()],
+        bump,
+    )]
+    pub bond: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// After the deadline, anyone can propose the outcome of an optimistic market by
+/// posting the market's bond. The proposal stands unless disputed in time.
+pub fn handler(ctx: Context<ProposeOutcome>, outcome: bool) -> Result<()> {
+    let market = &mut ctx.accounts.market;
+    let clock = Clock::get()?;
+
+    // Validations
+    ctx.accounts.protocol.require_unpaused(Protocol::PAUSE_RESOLVE)?;
+    market.require_unfrozen()?;
+    let (bond, challenge_period) = market
+        .optimistic_terms()
+        .ok_or(ClawBetsError::InvalidMarketKind)?;
+    require!(
+        market.status == MarketStatus::Open || market.status == MarketStatus::Closed,
+        ClawBetsError::MarketNotOpen
+    );
+    require!(market.proposal.is_none(), ClawBetsError::AlreadyProposed);
+    require!(clock.unix_timestamp >= market.deadline, ClawBetsError::MarketNotReady);
+    require!(
+        clock.unix_timestamp <= market.resolution_deadline,
+        ClawBetsError::ResolutionExpired
+    );
+
+    system_program::transfer(
+        CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.proposer.to_account_info(),
+                to: ctx.accounts.bond.to_account_info(),
+            },
+        ),
+        bond,
+    )?;
+
+    market.status = MarketStatus::Proposed;
+    market.proposal = Some(Proposal {
+        proposer: ctx.accounts.proposer.key(),
+        outcome,
+        proposed_at: clock.unix_timestamp,
+        disputer: None,
+        disputed_at: None,
+        bond_bump: ctx.bumps.bond,
+    });
+
+    let challenge_ends_at = clock
+        .unix_timestamp
+        .checked_add(challenge_period)
+        .ok_or(ClawBetsError::Overflow)?;
+
+    msg!(
+        "Market {}: {} proposed {} with a {} lamport bond, challenge period ends at {}",
+        market.market_id,
+        ctx.accounts.proposer.key(),
+        if outcome { "YES" } else { "NO" },
+        bond,
+        challenge_ends_at
+    );
+
+    emit_cpi!(OutcomeProposed {
+        version: EVENT_VERSION,
+        market: market.key(),
+        market_id: market.market_id,
+        proposer: ctx.accounts.proposer.key(),
+        outcome,
+        bond,
+        challenge_ends_at,
+    });
+    Ok(())
+}
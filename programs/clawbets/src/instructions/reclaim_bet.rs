@@ -1,13 +1,19 @@
 use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 use crate::state::*;
 use crate::errors::ClawBetsError;
+use crate::events::*;
+use crate::{collateral, payout};
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct ReclaimBet<'info> {
     #[account(mut)]
     pub bettor: Signer<'info>,
 
     #[account(
+        mut,
         seeds = [b"market", market.market_id.to_le_bytes().as_ref()],
         bump = market.bump,
     )]
@@ -31,43 +37,103 @@ pub struct ReclaimBet<'info> {
     )]
     pub vault: SystemAccount<'info>,
 
+    /// Collateral mint (token markets only)
+    pub collateral_mint: Option<InterfaceAccount<'info, Mint>>,
+
+    /// Token vault owned by the vault PDA (token markets only)
+    #[account(
+        mut,
+        seeds = [b"token_vault", market.key().as_ref()],
+        bump,
+    )]
+    pub vault_token: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Bettor's token account (token markets only)
+    #[account(
+        mut,
+        token::mint = collateral_mint,
+        token::authority = bettor,
+    )]
+    pub bettor_token: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+
     pub system_program: Program<'info, System>,
 }
 
 pub fn handler(ctx: Context<ReclaimBet>) -> Result<()> {
-    let market = &ctx.accounts.market;
+    let market_key = ctx.accounts.market.key();
+    let market = &mut ctx.accounts.market;
     let bet = &ctx.accounts.bet;
 
     // Allow reclaim if:
     // 1. Market is cancelled
     // 2. Market expired (past resolution deadline without resolution)
     // 3. Market resolved but winning pool is zero (no winners exist, losers get refund)
+    // 4. Market voided at resolution (oracle too uncertain to pick a side)
+    // 5. Market resolved on a barrier touch and the bet took YES after it
     let is_cancelled = market.status == MarketStatus::Cancelled;
+    let is_void = market.status == MarketStatus::Void;
+    // Pending optimistic proposals and disputes wait for their resolution instead
     let is_expired = market.status == MarketStatus::Expired
-        || (market.status != MarketStatus::Resolved
+        || ((market.status == MarketStatus::Open || market.status == MarketStatus::Closed)
             && Clock::get()?.unix_timestamp > market.resolution_deadline);
-    let is_resolved_no_winners = market.status == MarketStatus::Resolved && {
-        let outcome = market.outcome.unwrap_or(false);
-        let winning_pool = if outcome { market.total_yes } else { market.total_no };
-        winning_pool == 0
-    };
+    let is_resolved_no_winners =
+        market.status == MarketStatus::Resolved && !market.has_winners()?;
+    let is_late = market.status == MarketStatus::Resolved && market.is_late_bet(bet);
     require!(
-        is_cancelled || is_expired || is_resolved_no_winners,
+        is_cancelled || is_void || is_expired || is_resolved_no_winners || is_late,
         ClawBetsError::MarketNotReclaimable
     );
 
-    require!(!bet.claimed, ClawBetsError::AlreadyClaimed);
-
-    let amount = bet.amount;
+    // Stake plus a pro-rata share of any early-exit penalties, which go to the
+    // winners instead when a late bet is refunded
+    let amount = if is_late {
+        bet.amount
+    } else {
+        payout::refund(bet.amount, market.total_pool()?, market.penalty_pool)?
+    };
 
     // Transfer from vault back to bettor
-    **ctx.accounts.vault.to_account_info().try_borrow_mut_lamports()? -= amount;
-    **ctx.accounts.bettor.to_account_info().try_borrow_mut_lamports()? += amount;
+    let vault_seeds: &[&[u8]] = &[b"vault", market_key.as_ref(), &[market.vault_bump]];
+    match collateral::for_market(market, &ctx.accounts.collateral_mint, &ctx.accounts.token_program)? {
+        None => {
+            system_program::transfer(
+                CpiContext::new_with_signer(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.vault.to_account_info(),
+                        to: ctx.accounts.bettor.to_account_info(),
+                    },
+                    &[vault_seeds],
+                ),
+                amount,
+            )?;
+        }
+        Some(token) => {
+            token.transfer(
+                collateral::required(&ctx.accounts.vault_token)?,
+                collateral::required(&ctx.accounts.bettor_token)?,
+                ctx.accounts.vault.to_account_info(),
+                &[vault_seeds],
+                amount,
+            )?;
+        }
+    }
+    market.record_settled_bet()?;
 
     msg!(
-        "Reclaimed {} lamports from market {}",
+        "Reclaimed {} from market {}",
         amount,
         market.market_id
     );
+
+    emit_cpi!(BetReclaimed {
+        version: EVENT_VERSION,
+        market: market.key(),
+        bettor: bet.bettor,
+        stake: bet.amount,
+        amount,
+    });
     Ok(())
 }
@@ -0,0 +1,91 @@
+use anchor_lang::prelude::*;
+use pyth_solana_receiver_sdk::price_update::PriceUpdateV2;
+use crate::state::*;
+use crate::errors::ClawBetsError;
+use crate::events::*;
+use crate::oracle;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct RecordObservation<'info> {
+    #[account(mut)]
+    pub recorder: Signer<'info>,
+
+    #[account(
+        seeds = [b"market", market.market_id.to_le_bytes().as_ref()],
+        bump = market.bump,
+    )]
+    pub market: Account<'info, Market>,
+
+    #[account(
+        seeds = [b"protocol"],
+        bump = protocol.bump,
+    )]
+    pub protocol: Account<'info, Protocol>,
+
+    /// Observation log — created by the first observation
+    #[account(
+        init_if_needed,
+        payer = recorder,
+        space = 8 + Observations::INIT_SPACE,
+        seeds = [b"observations", market.key().as_ref()],
+        bump,
+    )]
+    pub observations: Account<'info, Observations>,
+
+    /// Pyth PriceUpdateV2 account published during the settlement window
+    pub price_update: Account<'info, PriceUpdateV2>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Anyone can append a price published during a TWAP market's settlement window.
+/// Observations must arrive in publish order, starting with the settlement price.
+pub fn handler(ctx: Context<RecordObservation>) -> Result<()> {
+    let market = &ctx.accounts.market;
+
+    // Validations
+    ctx.accounts.protocol.require_unpaused(Protocol::PAUSE_RESOLVE)?;
+    market.require_unfrozen()?;
+    require!(
+        market.status == MarketStatus::Open || market.status == MarketStatus::Closed,
+        ClawBetsError::MarketNotOpen
+    );
+
+    let observations = &mut ctx.accounts.observations;
+    let price = oracle::check_observation(market, observations, &ctx.accounts.price_update)?;
+
+    if observations.market == Pubkey::default() {
+        observations.market = market.key();
+        observations.exponent = price.exponent;
+        observations.bump = ctx.bumps.observations;
+    }
+    observations.observations.push(Observation {
+        price: price.price,
+        conf: price.conf,
+        publish_time: price.publish_time,
+    });
+    let count = observations.observations.len() as u16;
+
+    msg!(
+        "Observation {} for market {}: price=({} * 10^{} ± {}) at {}",
+        count,
+        market.market_id,
+        price.price,
+        price.exponent,
+        price.conf,
+        price.publish_time
+    );
+
+    emit_cpi!(ObservationRecorded {
+        version: EVENT_VERSION,
+        market: market.key(),
+        recorder: ctx.accounts.recorder.key(),
+        price: price.price,
+        conf: price.conf,
+        exponent: price.exponent,
+        publish_time: price.publish_time,
+        count,
+    });
+    Ok(())
+}
@@ -0,0 +1,79 @@
+use anchor_lang::prelude::*;
+use pyth_solana_receiver_sdk::price_update::PriceUpdateV2;
+use crate::state::*;
+use crate::errors::ClawBetsError;
+use crate::events::*;
+use crate::oracle;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct RecordTouch<'info> {
+    pub recorder: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"market", market.market_id.to_le_bytes().as_ref()],
+        bump = market.bump,
+    )]
+    pub market: Account<'info, Market>,
+
+    #[account(
+        seeds = [b"protocol"],
+        bump = protocol.bump,
+    )]
+    pub protocol: Account<'info, Protocol>,
+
+    /// Pyth PriceUpdateV2 account published before the deadline that reaches the barrier
+    pub price_update: Account<'info, PriceUpdateV2>,
+}
+
+/// Anyone can prove that a barrier market's target was reached before the deadline.
+/// The proof is stored on the market, which can then resolve YES immediately.
+pub fn handler(ctx: Context<RecordTouch>) -> Result<()> {
+    let market = &mut ctx.accounts.market;
+    let clock = Clock::get()?;
+
+    // Validations
+    ctx.accounts.protocol.require_unpaused(Protocol::PAUSE_RESOLVE)?;
+    market.require_unfrozen()?;
+    require!(market.is_barrier(), ClawBetsError::InvalidMarketKind);
+    require!(
+        market.status == MarketStatus::Open || market.status == MarketStatus::Closed,
+        ClawBetsError::MarketNotOpen
+    );
+    require!(market.touch.is_none(), ClawBetsError::AlreadyTouched);
+
+    let price_update = &ctx.accounts.price_update;
+    let price = oracle::check_touch(market, price_update)?;
+
+    let proof = TouchProof {
+        price: price.price,
+        conf: price.conf,
+        exponent: price.exponent,
+        publish_time: price.publish_time,
+        price_update: price_update.key(),
+        posted_slot: price_update.posted_slot,
+        recorder: ctx.accounts.recorder.key(),
+        recorded_at: clock.unix_timestamp,
+    };
+    market.touch = Some(proof);
+
+    msg!(
+        "Market {} barrier touched: price=({} * 10^{} ± {}) at {}, target=({} * 10^{})",
+        market.market_id,
+        price.price,
+        price.exponent,
+        price.conf,
+        price.publish_time,
+        market.target_price,
+        market.expected_exponent
+    );
+
+    emit_cpi!(BarrierTouched {
+        version: EVENT_VERSION,
+        market: market.key(),
+        market_id: market.market_id,
+        proof,
+    });
+    Ok(())
+}
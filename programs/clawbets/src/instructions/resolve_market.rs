@@ -1,8 +1,11 @@
 use anchor_lang::prelude::*;
-use pyth_solana_receiver_sdk::price_update::PriceUpdateV2;
+use pyth_solana_receiver_sdk::price_update::{Price, PriceUpdateV2};
 use crate::state::*;
 use crate::errors::ClawBetsError;
+use crate::events::*;
+use crate::oracle::{self, Settlement};
 
+#[event_cpi]
 #[derive(Accounts)]
 pub struct ResolveMarket<'info> {
     #[account(mut)]
@@ -15,20 +18,59 @@ pub struct ResolveMarket<'info> {
     )]
     pub market: Account<'info, Market>,
 
+    #[account(
+        seeds = [b"protocol"],
+        bump = protocol.bump,
+    )]
+    pub protocol: Account<'info, Protocol>,
+
     /// Pyth PriceUpdateV2 account — posted on-chain via Hermes + Pyth receiver.
     /// Anchor automatically validates this is owned by the Pyth receiver program.
-    pub price_update: Account<'info, PriceUpdateV2>,
+    /// Not needed for a barrier market that has been touched, or a TWAP market.
+    pub price_update: Option<Account<'info, PriceUpdateV2>>,
+
+    /// Pyth PriceUpdateV2 account for the second feed (relative markets only)
+    pub price_update_b: Option<Account<'info, PriceUpdateV2>>,
+
+    /// Whitelist entry for the market's feed
+    #[account(
+        seeds = [b"feed", market.feed_id.as_ref()],
+        bump = feed.bump,
+    )]
+    pub feed: Account<'info, FeedConfig>,
+
+    /// Whitelist entry for the second feed (relative markets only)
+    #[account(
+        seeds = [b"feed", feed_b.feed_id.as_ref()],
+        bump = feed_b.bump,
+    )]
+    pub feed_b: Option<Account<'info, FeedConfig>>,
+
+    /// Observation log (TWAP markets only)
+    #[account(
+        seeds = [b"observations", market.key().as_ref()],
+        bump = observations.bump,
+    )]
+    pub observations: Option<Account<'info, Observations>>,
 }
 
 pub fn handler(ctx: Context<ResolveMarket>) -> Result<()> {
     let market = &mut ctx.accounts.market;
     let clock = Clock::get()?;
 
-    // Must be past deadline
-    require!(
-        clock.unix_timestamp >= market.deadline,
-        ClawBetsError::MarketNotReady
-    );
+    ctx.accounts.protocol.require_unpaused(Protocol::PAUSE_RESOLVE)?;
+    market.require_unfrozen()?;
+
+    // Must be past deadline — a touched barrier can resolve YES early, and an
+    // untouched one waits out the price window so pending touch proofs can land
+    if market.touch.is_none() {
+        let ready_at = if market.is_barrier() {
+            market.deadline.checked_add(market.price_window).ok_or(ClawBetsError::Overflow)?
+        } else {
+            market.deadline
+        };
+        require!(clock.unix_timestamp >= ready_at, ClawBetsError::MarketNotReady);
+    }
 
     // Must not be past resolution deadline
     require!(
@@ -42,33 +84,110 @@ pub fn handler(ctx: Context<ResolveMarket>) -> Result<()> {
         ClawBetsError::MarketNotOpen
     );
 
-    // Get price from Pyth PriceUpdateV2 — validates feed_id and staleness
-    let price_update = &ctx.accounts.price_update;
-    let maximum_age: u64 = 120; // 2 minutes max staleness
-    let price = price_update
-        .get_price_no_older_than(&clock, maximum_age, &market.feed_id)
-        .map_err(|_| ClawBetsError::InvalidOracleData)?;
-
-    // Determine outcome
-    let outcome = if market.target_above {
-        price.price >= market.target_price
-    } else {
-        price.price < market.target_price
+    let observation_count = match (&market.twap, &ctx.accounts.observations) {
+        (Some(_), Some(observations)) => Some(observations.observations.len() as u16),
+        _ => None,
     };
+    let mut price_b = None;
+    let (price, settlement) = match market.touch {
+        Some(touch) => (
+            Price {
+                price: touch.price,
+                conf: touch.conf,
+                exponent: touch.exponent,
+                publish_time: touch.publish_time,
+            },
+            Settlement::Outcome(true),
+        ),
+        None if market.twap.is_some() => {
+            let observations = ctx
+                .accounts
+                .observations
+                .as_ref()
+                .ok_or(ClawBetsError::NotEnoughObservations)?;
+            let min_observations = market.twap.map_or(0, |twap| twap.min_observations);
+            let price = oracle::twap(observations, min_observations)?;
+            (price, oracle::settle_price(market, &price)?)
+        }
+        None if market.feed_b().is_some() => {
+            let (price_update_a, price_update_b) =
+                match (&ctx.accounts.price_update, &ctx.accounts.price_update_b) {
+                    (Some(a), Some(b)) => (a, b),
+                    _ => return err!(ClawBetsError::MissingFeedPrice),
+                };
+            let (price_a, settled_b, settlement) =
+                oracle::settle_relative(market, price_update_a, price_update_b)?;
+            price_b = Some(settled_b);
+            (price_a, settlement)
+        }
+        None => {
+            let price_update = ctx
+                .accounts
+                .price_update
+                .as_ref()
+                .ok_or(ClawBetsError::MissingPriceUpdate)?;
+            oracle::settle(market, price_update)?
+        }
+    };
+    // The feed must still be whitelisted, and the price fresh enough for the
+    // staleness bound the market was created with
+    let feed = &ctx.accounts.feed;
+    oracle::check_feed(feed, price.exponent)?;
+    oracle::check_staleness(price.publish_time, market.deadline, market.max_staleness)?;
+    if let Some(price_b) = price_b {
+        let feed_b = ctx.accounts.feed_b.as_ref().ok_or(ClawBetsError::FeedNotWhitelisted)?;
+        require!(Some(feed_b.feed_id) == market.feed_b(), ClawBetsError::FeedNotWhitelisted);
+        oracle::check_feed(feed_b, price_b.exponent)?;
+        oracle::check_staleness(price_b.publish_time, market.deadline, market.max_staleness)?;
+    }
 
-    market.status = MarketStatus::Resolved;
-    market.outcome = Some(outcome);
+    // Claims open only once the dispute period passes without the outcome changing
+    let finalizes_at = clock
+        .unix_timestamp
+        .checked_add(ctx.accounts.protocol.dispute_period)
+        .ok_or(ClawBetsError::Overflow)?;
+    market.status = MarketStatus::Resolving;
+    market.finalizes_at = Some(finalizes_at);
+    market.apply_settlement(settlement);
     market.resolved_price = Some(price.price);
+    market.resolved_exponent = Some(price.exponent);
+    market.resolved_publish_time = Some(price.publish_time);
     market.resolved_at = Some(clock.unix_timestamp);
 
     msg!(
-        "Market {} resolved: price=({} * 10^{}), target={}, above={}, outcome={}",
+        "Market {} resolved: price=({} * 10^{} ± {}) at {}, target=({} * 10^{}), above={}, outcome={}, final at {}",
         market.market_id,
         price.price,
         price.exponent,
+        price.conf,
+        price.publish_time,
         market.target_price,
+        market.expected_exponent,
         market.target_above,
-        if outcome { "YES wins" } else { "NO wins" }
+        settlement.describe(),
+        finalizes_at
     );
+
+    emit_cpi!(MarketResolved {
+        version: EVENT_VERSION,
+        market: market.key(),
+        market_id: market.market_id,
+        outcome: market.outcome,
+        winning_bucket: market.winning_bucket,
+        scalar_offset: market.scalar_offset,
+        observations: observation_count,
+        price_b: price_b.map(|price| price.price),
+        exponent_b: price_b.map(|price| price.exponent),
+        price: price.price,
+        conf: price.conf,
+        exponent: price.exponent,
+        publish_time: price.publish_time,
+        total_yes: market.total_yes,
+        total_no: market.total_no,
+        bucket_totals: market.bucket_totals.clone(),
+        penalty_pool: market.penalty_pool,
+        resolved_at: clock.unix_timestamp,
+        finalizes_at,
+    });
     Ok(())
 }
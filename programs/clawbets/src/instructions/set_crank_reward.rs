@@ -0,0 +1,38 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::errors::ClawBetsError;
+use crate::events::*;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SetCrankReward<'info> {
+    pub fee_manager: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"protocol"],
+        bump = protocol.bump,
+        has_one = fee_manager @ ClawBetsError::UnauthorizedRole,
+    )]
+    pub protocol: Account<'info, Protocol>,
+}
+
+pub fn handler(ctx: Context<SetCrankReward>, crank_reward_bps: u16) -> Result<()> {
+    require!(
+        crank_reward_bps <= Protocol::MAX_CRANK_REWARD_BPS,
+        ClawBetsError::FeeTooHigh
+    );
+
+    let protocol = &mut ctx.accounts.protocol;
+    let old_crank_reward_bps = protocol.crank_reward_bps;
+    protocol.crank_reward_bps = crank_reward_bps;
+
+    msg!("Crank reward set to {} bps of the protocol fee", crank_reward_bps);
+
+    emit_cpi!(CrankRewardUpdated {
+        version: EVENT_VERSION,
+        old_crank_reward_bps,
+        crank_reward_bps,
+    });
+    Ok(())
+}
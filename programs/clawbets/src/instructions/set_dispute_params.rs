@@ -0,0 +1,47 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::errors::ClawBetsError;
+use crate::events::*;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SetDisputeParams<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"protocol"],
+        bump = protocol.bump,
+        has_one = admin @ ClawBetsError::UnauthorizedAdmin,
+    )]
+    pub protocol: Account<'info, Protocol>,
+}
+
+pub fn handler(ctx: Context<SetDisputeParams>, dispute_period: i64, challenge_bond: u64) -> Result<()> {
+    require!(
+        (0..=Protocol::MAX_DISPUTE_PERIOD).contains(&dispute_period),
+        ClawBetsError::InvalidDisputeParams
+    );
+    // The bond escrow starts empty, so the bond must cover its rent exemption
+    require!(
+        challenge_bond >= Rent::get()?.minimum_balance(0),
+        ClawBetsError::InvalidDisputeParams
+    );
+
+    let protocol = &mut ctx.accounts.protocol;
+    protocol.dispute_period = dispute_period;
+    protocol.challenge_bond = challenge_bond;
+
+    msg!(
+        "Dispute period set to {}s with a {} lamport challenge bond",
+        dispute_period,
+        challenge_bond
+    );
+
+    emit_cpi!(DisputeParamsUpdated {
+        version: EVENT_VERSION,
+        dispute_period,
+        challenge_bond,
+    });
+    Ok(())
+}
@@ -0,0 +1,35 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::errors::ClawBetsError;
+use crate::events::*;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SetFee<'info> {
+    pub fee_manager: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"protocol"],
+        bump = protocol.bump,
+        has_one = fee_manager @ ClawBetsError::UnauthorizedRole,
+    )]
+    pub protocol: Account<'info, Protocol>,
+}
+
+pub fn handler(ctx: Context<SetFee>, fee_bps: u16) -> Result<()> {
+    require!(fee_bps <= Protocol::MAX_FEE_BPS, ClawBetsError::FeeTooHigh);
+
+    let protocol = &mut ctx.accounts.protocol;
+    let old_fee_bps = protocol.fee_bps;
+    protocol.fee_bps = fee_bps;
+
+    msg!("Protocol fee set to {} bps", fee_bps);
+
+    emit_cpi!(FeeUpdated {
+        version: EVENT_VERSION,
+        old_fee_bps,
+        fee_bps,
+    });
+    Ok(())
+}
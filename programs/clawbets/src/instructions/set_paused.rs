@@ -0,0 +1,36 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::errors::ClawBetsError;
+use crate::events::*;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SetPaused<'info> {
+    pub pauser: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"protocol"],
+        bump = protocol.bump,
+        has_one = pauser @ ClawBetsError::UnauthorizedRole,
+    )]
+    pub protocol: Account<'info, Protocol>,
+}
+
+/// Replace the set of paused operation classes. Pass 0 to unpause everything.
+pub fn handler(ctx: Context<SetPaused>, paused: u8) -> Result<()> {
+    require!(paused & !Protocol::PAUSE_ALL == 0, ClawBetsError::InvalidPauseFlags);
+
+    let protocol = &mut ctx.accounts.protocol;
+    let old_paused = protocol.paused;
+    protocol.paused = paused;
+
+    msg!("Pause flags set to {:#06b}", paused);
+
+    emit_cpi!(PauseUpdated {
+        version: EVENT_VERSION,
+        old_paused,
+        paused,
+    });
+    Ok(())
+}
@@ -0,0 +1,35 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::errors::ClawBetsError;
+use crate::events::*;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SetRole<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"protocol"],
+        bump = protocol.bump,
+        has_one = admin @ ClawBetsError::UnauthorizedAdmin,
+    )]
+    pub protocol: Account<'info, Protocol>,
+}
+
+/// Delegate a protocol role to `key`. Every role starts out held by the admin.
+pub fn handler(ctx: Context<SetRole>, role: Role, key: Pubkey) -> Result<()> {
+    let protocol = &mut ctx.accounts.protocol;
+    let old_key = protocol.role(role);
+    protocol.set_role(role, key);
+
+    msg!("Role {:?} moved from {} to {}", role, old_key, key);
+
+    emit_cpi!(RoleUpdated {
+        version: EVENT_VERSION,
+        role,
+        old_key,
+        key,
+    });
+    Ok(())
+}
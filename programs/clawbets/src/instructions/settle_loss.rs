@@ -0,0 +1,83 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::errors::ClawBetsError;
+use crate::events::*;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SettleLoss<'info> {
+    #[account(mut)]
+    pub caller: Signer<'info>,
+
+    /// CHECK: Losing bettor — receives the bet account's rent
+    #[account(mut)]
+    pub bettor: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"market", market.market_id.to_le_bytes().as_ref()],
+        bump = market.bump,
+    )]
+    pub market: Account<'info, Market>,
+
+    #[account(
+        mut,
+        seeds = [b"bet", market.key().as_ref(), bettor.key().as_ref()],
+        bump = bet.bump,
+        has_one = bettor,
+        has_one = market,
+        close = bettor,
+    )]
+    pub bet: Account<'info, Bet>,
+
+    #[account(
+        mut,
+        seeds = [b"reputation", bettor.key().as_ref()],
+        bump = reputation.bump,
+    )]
+    pub reputation: Account<'info, AgentReputation>,
+}
+
+/// Anyone can call this on a resolved market to record a losing bet against the
+/// bettor's reputation. The bet account is closed and its rent returned to the bettor.
+pub fn handler(ctx: Context<SettleLoss>) -> Result<()> {
+    let market = &mut ctx.accounts.market;
+    let bet = &ctx.accounts.bet;
+
+    // Validations
+    require!(market.status == MarketStatus::Resolved, ClawBetsError::MarketNotResolved);
+    require!(!market.is_winning_bet(bet)?, ClawBetsError::BetDidNotLose);
+    // Late barrier bets are refunded through reclaim_bet instead
+    require!(!market.is_late_bet(bet), ClawBetsError::LateBet);
+
+    // With no winners the losing side is refunded through reclaim_bet instead
+    require!(market.has_winners()?, ClawBetsError::NoWinners);
+
+    market.record_settled_bet()?;
+
+    // Update reputation
+    let rep = &mut ctx.accounts.reputation;
+    rep.losses = rep.losses.checked_add(1).ok_or(ClawBetsError::Overflow)?;
+    if market.collateral_mint.is_none() {
+        rep.total_lost = rep.total_lost.checked_add(bet.amount).ok_or(ClawBetsError::Overflow)?;
+    }
+
+    // Recalculate accuracy
+    rep.recompute_accuracy()?;
+    rep.last_active = Clock::get()?.unix_timestamp;
+
+    msg!(
+        "Settled loss of {} for {} on market {}",
+        bet.amount,
+        bet.bettor,
+        market.market_id
+    );
+
+    emit_cpi!(LossSettled {
+        version: EVENT_VERSION,
+        market: market.key(),
+        bettor: bet.bettor,
+        amount: bet.amount,
+    });
+    Ok(())
+}
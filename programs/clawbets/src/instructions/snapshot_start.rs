@@ -0,0 +1,71 @@
+use anchor_lang::prelude::*;
+use pyth_solana_receiver_sdk::price_update::PriceUpdateV2;
+use crate::state::*;
+use crate::errors::ClawBetsError;
+use crate::events::*;
+use crate::oracle;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct SnapshotStart<'info> {
+    pub caller: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"market", market.market_id.to_le_bytes().as_ref()],
+        bump = market.bump,
+    )]
+    pub market: Account<'info, Market>,
+
+    #[account(
+        seeds = [b"protocol"],
+        bump = protocol.bump,
+    )]
+    pub protocol: Account<'info, Protocol>,
+
+    /// Pyth PriceUpdateV2 account for `feed_id`
+    pub price_update: Account<'info, PriceUpdateV2>,
+
+    /// Pyth PriceUpdateV2 account for the market's second feed
+    pub price_update_b: Account<'info, PriceUpdateV2>,
+}
+
+/// Anyone can record a relative market's start prices if they were not supplied
+/// at creation. Both must be published within the price window of creation.
+pub fn handler(ctx: Context<SnapshotStart>) -> Result<()> {
+    let market = &mut ctx.accounts.market;
+
+    // Validations
+    ctx.accounts.protocol.require_unpaused(Protocol::PAUSE_RESOLVE)?;
+    market.require_unfrozen()?;
+    require!(market.status == MarketStatus::Open, ClawBetsError::MarketNotOpen);
+    require!(market.start_prices.is_none(), ClawBetsError::StartPricesAlreadySet);
+
+    let (price_a, price_b) = oracle::check_start_prices(
+        market,
+        &ctx.accounts.price_update,
+        &ctx.accounts.price_update_b,
+    )?;
+    let start_prices = StartPrices {
+        a: oracle::snapshot(&price_a),
+        b: oracle::snapshot(&price_b),
+    };
+    market.start_prices = Some(start_prices);
+
+    msg!(
+        "Market {} start prices: ({} * 10^{}) and ({} * 10^{})",
+        market.market_id,
+        price_a.price,
+        price_a.exponent,
+        price_b.price,
+        price_b.exponent
+    );
+
+    emit_cpi!(StartPricesRecorded {
+        version: EVENT_VERSION,
+        market: market.key(),
+        market_id: market.market_id,
+        start_prices,
+    });
+    Ok(())
+}
@@ -0,0 +1,54 @@
+use anchor_lang::prelude::*;
+use crate::state::*;
+use crate::errors::ClawBetsError;
+use crate::events::*;
+use super::add_feed::validate_feed_config;
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct UpdateFeed<'info> {
+    pub feed_curator: Signer<'info>,
+
+    #[account(
+        seeds = [b"protocol"],
+        bump = protocol.bump,
+        has_one = feed_curator @ ClawBetsError::UnauthorizedRole,
+    )]
+    pub protocol: Account<'info, Protocol>,
+
+    #[account(
+        mut,
+        seeds = [b"feed", feed.feed_id.as_ref()],
+        bump = feed.bump,
+    )]
+    pub feed: Account<'info, FeedConfig>,
+}
+
+/// Change a whitelisted feed's metadata, or re-enable it.
+pub fn handler(
+    ctx: Context<UpdateFeed>,
+    symbol: String,
+    exponent: i32,
+    max_staleness: i64,
+    enabled: bool,
+) -> Result<()> {
+    validate_feed_config(&symbol, exponent, max_staleness)?;
+
+    let feed = &mut ctx.accounts.feed;
+    feed.symbol = symbol;
+    feed.exponent = exponent;
+    feed.max_staleness = max_staleness;
+    feed.enabled = enabled;
+
+    msg!("Feed {} updated (enabled: {})", feed.symbol, enabled);
+
+    emit_cpi!(FeedUpdated {
+        version: EVENT_VERSION,
+        feed_id: feed.feed_id,
+        symbol: feed.symbol.clone(),
+        exponent,
+        max_staleness,
+        enabled,
+    });
+    Ok(())
+}
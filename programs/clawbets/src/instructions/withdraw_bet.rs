@@ -0,0 +1,167 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::state::*;
+use crate::errors::ClawBetsError;
+use crate::events::*;
+use crate::{collateral, payout};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct WithdrawBet<'info> {
+    #[account(mut)]
+    pub bettor: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"market", market.market_id.to_le_bytes().as_ref()],
+        bump = market.bump,
+    )]
+    pub market: Account<'info, Market>,
+
+    #[account(
+        mut,
+        seeds = [b"bet", market.key().as_ref(), bettor.key().as_ref()],
+        bump = bet.bump,
+        has_one = bettor,
+        has_one = market,
+        close = bettor,
+    )]
+    pub bet: Account<'info, Bet>,
+
+    /// CHECK: Vault PDA holding escrowed SOL
+    #[account(
+        mut,
+        seeds = [b"vault", market.key().as_ref()],
+        bump = market.vault_bump,
+    )]
+    pub vault: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"reputation", bettor.key().as_ref()],
+        bump = reputation.bump,
+    )]
+    pub reputation: Account<'info, AgentReputation>,
+
+    #[account(
+        mut,
+        seeds = [b"protocol"],
+        bump = protocol.bump,
+    )]
+    pub protocol: Account<'info, Protocol>,
+
+    /// Collateral mint (token markets only)
+    pub collateral_mint: Option<InterfaceAccount<'info, Mint>>,
+
+    /// Token vault owned by the vault PDA (token markets only)
+    #[account(
+        mut,
+        seeds = [b"token_vault", market.key().as_ref()],
+        bump,
+    )]
+    pub vault_token: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Bettor's token account (token markets only)
+    #[account(
+        mut,
+        token::mint = collateral_mint,
+        token::authority = bettor,
+    )]
+    pub bettor_token: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Exit a bet before the betting deadline. The stake is refunded minus the market's
+/// exit penalty, which stays in the pool for the remaining bettors.
+pub fn handler(ctx: Context<WithdrawBet>) -> Result<()> {
+    let market_key = ctx.accounts.market.key();
+    let market = &mut ctx.accounts.market;
+    let bet = &ctx.accounts.bet;
+    let clock = Clock::get()?;
+
+    // Validations
+    market.require_betting_open(clock.unix_timestamp)?;
+    // Shares bought from the pool can't be refunded at cost without draining it
+    require!(!market.is_amm(), ClawBetsError::AmmWithdrawalUnsupported);
+
+    let amount = bet.amount;
+    let penalty = payout::bps_of(amount, market.exit_penalty_bps)?;
+    let refund = amount - penalty;
+
+    // Transfer from vault back to bettor
+    let vault_seeds: &[&[u8]] = &[b"vault", market_key.as_ref(), &[market.vault_bump]];
+    match collateral::for_market(market, &ctx.accounts.collateral_mint, &ctx.accounts.token_program)? {
+        None => {
+            system_program::transfer(
+                CpiContext::new_with_signer(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.vault.to_account_info(),
+                        to: ctx.accounts.bettor.to_account_info(),
+                    },
+                    &[vault_seeds],
+                ),
+                refund,
+            )?;
+        }
+        Some(token) => {
+            token.transfer(
+                collateral::required(&ctx.accounts.vault_token)?,
+                collateral::required(&ctx.accounts.bettor_token)?,
+                ctx.accounts.vault.to_account_info(),
+                &[vault_seeds],
+                refund,
+            )?;
+        }
+    }
+    let is_native = market.collateral_mint.is_none();
+
+    // Reverse market totals — the penalty moves to the penalty pool
+    market.remove_stake(bet)?;
+    market.penalty_pool = market.penalty_pool.checked_add(penalty).ok_or(ClawBetsError::Overflow)?;
+
+    // Reverse protocol volume (SOL only)
+    let protocol = &mut ctx.accounts.protocol;
+    if is_native {
+        protocol.total_volume = protocol.total_volume.checked_sub(amount).ok_or(ClawBetsError::Overflow)?;
+    }
+
+    // Reverse reputation
+    let rep = &mut ctx.accounts.reputation;
+    rep.total_bets = rep.total_bets.checked_sub(1).ok_or(ClawBetsError::Overflow)?;
+    if is_native {
+        rep.total_wagered = rep.total_wagered.checked_sub(amount).ok_or(ClawBetsError::Overflow)?;
+    }
+    rep.last_active = clock.unix_timestamp;
+
+    msg!(
+        "Withdrew {} from market {} (penalty: {})",
+        refund,
+        market.market_id,
+        penalty
+    );
+
+    emit_cpi!(BetWithdrawn {
+        version: EVENT_VERSION,
+        market: market.key(),
+        bettor: bet.bettor,
+        position: bet.position,
+        bucket: bet.bucket,
+        amount,
+        refund,
+        penalty,
+        total_yes: market.total_yes,
+        total_no: market.total_no,
+        yes_count: market.yes_count,
+        no_count: market.no_count,
+        bucket_totals: market.bucket_totals.clone(),
+        bucket_counts: market.bucket_counts.clone(),
+        penalty_pool: market.penalty_pool,
+        withdrawn_at: clock.unix_timestamp,
+    });
+    Ok(())
+}
@@ -0,0 +1,122 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::state::*;
+use crate::errors::ClawBetsError;
+use crate::events::*;
+use crate::collateral::{self, TokenCollateral};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct WithdrawFees<'info> {
+    pub fee_manager: Signer<'info>,
+
+    #[account(
+        seeds = [b"protocol"],
+        bump = protocol.bump,
+        has_one = fee_manager @ ClawBetsError::UnauthorizedRole,
+    )]
+    pub protocol: Account<'info, Protocol>,
+
+    /// CHECK: Treasury PDA holding collected fees
+    #[account(
+        mut,
+        seeds = [b"treasury"],
+        bump = protocol.treasury_bump,
+    )]
+    pub treasury: SystemAccount<'info>,
+
+    /// CHECK: Any account chosen by the fee manager to receive the fees
+    #[account(mut)]
+    pub recipient: SystemAccount<'info>,
+
+    /// Mint of the fees to withdraw — omit to withdraw SOL
+    pub collateral_mint: Option<InterfaceAccount<'info, Mint>>,
+
+    /// Treasury's associated token account for the mint
+    #[account(
+        mut,
+        associated_token::mint = collateral_mint,
+        associated_token::authority = treasury,
+        associated_token::token_program = token_program,
+    )]
+    pub treasury_token: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Recipient's token account for the mint
+    #[account(
+        mut,
+        token::mint = collateral_mint,
+    )]
+    pub recipient_token: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<WithdrawFees>, amount: u64) -> Result<()> {
+    let treasury_seeds: &[&[u8]] = &[b"treasury", &[ctx.accounts.protocol.treasury_bump]];
+
+    if let Some(mint) = &ctx.accounts.collateral_mint {
+        let token = TokenCollateral {
+            mint,
+            token_program: collateral::required(&ctx.accounts.token_program)?,
+        };
+        let treasury_token = collateral::required(&ctx.accounts.treasury_token)?;
+        require!(amount <= treasury_token.amount, ClawBetsError::InsufficientFees);
+
+        token.transfer(
+            treasury_token,
+            collateral::required(&ctx.accounts.recipient_token)?,
+            ctx.accounts.treasury.to_account_info(),
+            &[treasury_seeds],
+            amount,
+        )?;
+
+        msg!(
+            "Withdrew {} tokens of {} protocol fees to {}",
+            amount,
+            mint.key(),
+            ctx.accounts.recipient.key()
+        );
+
+        emit_cpi!(FeesWithdrawn {
+            version: EVENT_VERSION,
+            mint: Some(mint.key()),
+            recipient: ctx.accounts.recipient.key(),
+            amount,
+        });
+        return Ok(());
+    }
+
+    // The treasury keeps its rent-exempt minimum so it can keep receiving fees
+    let rent_exempt = Rent::get()?.minimum_balance(0);
+    let available = ctx.accounts.treasury.lamports().saturating_sub(rent_exempt);
+    require!(amount <= available, ClawBetsError::InsufficientFees);
+
+    system_program::transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.treasury.to_account_info(),
+                to: ctx.accounts.recipient.to_account_info(),
+            },
+            &[treasury_seeds],
+        ),
+        amount,
+    )?;
+
+    msg!(
+        "Withdrew {} lamports of protocol fees to {}",
+        amount,
+        ctx.accounts.recipient.key()
+    );
+
+    emit_cpi!(FeesWithdrawn {
+        version: EVENT_VERSION,
+        mint: None,
+        recipient: ctx.accounts.recipient.key(),
+        amount,
+    });
+    Ok(())
+}
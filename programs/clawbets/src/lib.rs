@@ -1,18 +1,62 @@
 use anchor_lang::prelude::*;
+use oracle::Settlement;
+use state::{CancelReason, MarketKind, Role, TwapConfig};
 
+pub mod amm;
+pub mod collateral;
 pub mod errors;
+pub mod events;
 pub mod instructions;
+pub mod migration;
+pub mod oracle;
+pub mod payout;
 pub mod state;
 
-pub use instructions::initialize::*;
-pub use instructions::create_market::*;
-pub use instructions::place_bet::*;
-pub use instructions::resolve_market::*;
-pub use instructions::claim_winnings::*;
-pub use instructions::cancel_market::*;
-pub use instructions::reclaim_bet::*;
-pub use instructions::expire_market::*;
-pub use instructions::close_betting::*;
+// Every instruction module exports a `handler`; the program calls them by path,
+// so the clashing glob re-exports are never used.
+#[allow(ambiguous_glob_reexports)]
+pub use instructions::{
+    initialize::*,
+    create_market::*,
+    place_bet::*,
+    resolve_market::*,
+    claim_winnings::*,
+    cancel_market::*,
+    reclaim_bet::*,
+    expire_market::*,
+    close_betting::*,
+    settle_loss::*,
+    set_fee::*,
+    withdraw_fees::*,
+    claim_creator_fees::*,
+    withdraw_bet::*,
+    record_touch::*,
+    record_observation::*,
+    snapshot_start::*,
+    propose_outcome::*,
+    dispute::*,
+    finalize_proposal::*,
+    arbitrate::*,
+    set_dispute_params::*,
+    challenge_resolution::*,
+    overturn_resolution::*,
+    finalize_resolution::*,
+    add_feed::*,
+    update_feed::*,
+    disable_feed::*,
+    propose_admin::*,
+    accept_admin::*,
+    set_role::*,
+    set_paused::*,
+    freeze_market::*,
+    finalize_market::*,
+    set_crank_reward::*,
+    distribute::*,
+    migrate_protocol::*,
+    migrate_market::*,
+    migrate_bet::*,
+    migrate_reputation::*,
+};
 
 declare_id!("3kBwjzUXtVeUshBWDD1Ls5PZPqQZgQUGNUTdP6jCqobb");
 
@@ -24,26 +68,42 @@ pub mod clawbets {
         instructions::initialize::handler(ctx)
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn create_market(
         ctx: Context<CreateMarket>,
         title: String,
         description: String,
         feed_id: [u8; 32],
         target_price: i64,
+        expected_exponent: i32,
         target_above: bool,
+        max_conf_bps: u16,
         deadline: i64,
         resolution_deadline: i64,
+        price_window: i64,
         min_bet: u64,
         max_bet: u64,
+        creator_fee_bps: u16,
+        exit_penalty_bps: u16,
+        kind: MarketKind,
+        twap: Option<TwapConfig>,
+        amm_liquidity: Option<u64>,
     ) -> Result<()> {
         instructions::create_market::handler(
-            ctx, title, description, feed_id, target_price,
-            target_above, deadline, resolution_deadline, min_bet, max_bet,
+            ctx, title, description, feed_id, target_price, expected_exponent,
+            target_above, max_conf_bps, deadline, resolution_deadline, price_window, min_bet, max_bet,
+            creator_fee_bps, exit_penalty_bps, kind, twap, amm_liquidity,
         )
     }
 
-    pub fn place_bet(ctx: Context<PlaceBet>, amount: u64, position: bool) -> Result<()> {
-        instructions::place_bet::handler(ctx, amount, position)
+    pub fn place_bet(
+        ctx: Context<PlaceBet>,
+        amount: u64,
+        position: bool,
+        bucket: u8,
+        min_shares_out: u64,
+    ) -> Result<()> {
+        instructions::place_bet::handler(ctx, amount, position, bucket, min_shares_out)
     }
 
     pub fn resolve_market(ctx: Context<ResolveMarket>) -> Result<()> {
@@ -54,8 +114,8 @@ pub mod clawbets {
         instructions::claim_winnings::handler(ctx)
     }
 
-    pub fn cancel_market(ctx: Context<CancelMarket>) -> Result<()> {
-        instructions::cancel_market::handler(ctx)
+    pub fn cancel_market(ctx: Context<CancelMarket>, reason: CancelReason) -> Result<()> {
+        instructions::cancel_market::handler(ctx, reason)
     }
 
     pub fn reclaim_bet(ctx: Context<ReclaimBet>) -> Result<()> {
@@ -69,4 +129,144 @@ pub mod clawbets {
     pub fn close_betting(ctx: Context<CloseBetting>) -> Result<()> {
         instructions::close_betting::handler(ctx)
     }
+
+    pub fn settle_loss(ctx: Context<SettleLoss>) -> Result<()> {
+        instructions::settle_loss::handler(ctx)
+    }
+
+    pub fn set_fee(ctx: Context<SetFee>, fee_bps: u16) -> Result<()> {
+        instructions::set_fee::handler(ctx, fee_bps)
+    }
+
+    pub fn withdraw_fees(ctx: Context<WithdrawFees>, amount: u64) -> Result<()> {
+        instructions::withdraw_fees::handler(ctx, amount)
+    }
+
+    pub fn claim_creator_fees(ctx: Context<ClaimCreatorFees>) -> Result<()> {
+        instructions::claim_creator_fees::handler(ctx)
+    }
+
+    pub fn withdraw_bet(ctx: Context<WithdrawBet>) -> Result<()> {
+        instructions::withdraw_bet::handler(ctx)
+    }
+
+    pub fn record_touch(ctx: Context<RecordTouch>) -> Result<()> {
+        instructions::record_touch::handler(ctx)
+    }
+
+    pub fn record_observation(ctx: Context<RecordObservation>) -> Result<()> {
+        instructions::record_observation::handler(ctx)
+    }
+
+    pub fn snapshot_start(ctx: Context<SnapshotStart>) -> Result<()> {
+        instructions::snapshot_start::handler(ctx)
+    }
+
+    pub fn propose_outcome(ctx: Context<ProposeOutcome>, outcome: bool) -> Result<()> {
+        instructions::propose_outcome::handler(ctx, outcome)
+    }
+
+    pub fn dispute(ctx: Context<Dispute>) -> Result<()> {
+        instructions::dispute::handler(ctx)
+    }
+
+    pub fn finalize_proposal(ctx: Context<FinalizeProposal>) -> Result<()> {
+        instructions::finalize_proposal::handler(ctx)
+    }
+
+    pub fn arbitrate(ctx: Context<Arbitrate>, outcome: bool) -> Result<()> {
+        instructions::arbitrate::handler(ctx, outcome)
+    }
+
+    pub fn set_dispute_params(
+        ctx: Context<SetDisputeParams>,
+        dispute_period: i64,
+        challenge_bond: u64,
+    ) -> Result<()> {
+        instructions::set_dispute_params::handler(ctx, dispute_period, challenge_bond)
+    }
+
+    pub fn challenge_resolution(ctx: Context<ChallengeResolution>, claim: Settlement) -> Result<()> {
+        instructions::challenge_resolution::handler(ctx, claim)
+    }
+
+    pub fn overturn_resolution(ctx: Context<OverturnResolution>, settlement: Settlement) -> Result<()> {
+        instructions::overturn_resolution::handler(ctx, settlement)
+    }
+
+    pub fn finalize_resolution(ctx: Context<FinalizeResolution>) -> Result<()> {
+        instructions::finalize_resolution::handler(ctx)
+    }
+
+    pub fn add_feed(
+        ctx: Context<AddFeed>,
+        feed_id: [u8; 32],
+        symbol: String,
+        exponent: i32,
+        max_staleness: i64,
+    ) -> Result<()> {
+        instructions::add_feed::handler(ctx, feed_id, symbol, exponent, max_staleness)
+    }
+
+    pub fn update_feed(
+        ctx: Context<UpdateFeed>,
+        symbol: String,
+        exponent: i32,
+        max_staleness: i64,
+        enabled: bool,
+    ) -> Result<()> {
+        instructions::update_feed::handler(ctx, symbol, exponent, max_staleness, enabled)
+    }
+
+    pub fn disable_feed(ctx: Context<DisableFeed>) -> Result<()> {
+        instructions::disable_feed::handler(ctx)
+    }
+
+    pub fn propose_admin(ctx: Context<ProposeAdmin>, new_admin: Pubkey) -> Result<()> {
+        instructions::propose_admin::handler(ctx, new_admin)
+    }
+
+    pub fn accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+        instructions::accept_admin::handler(ctx)
+    }
+
+    pub fn set_role(ctx: Context<SetRole>, role: Role, key: Pubkey) -> Result<()> {
+        instructions::set_role::handler(ctx, role, key)
+    }
+
+    pub fn set_paused(ctx: Context<SetPaused>, paused: u8) -> Result<()> {
+        instructions::set_paused::handler(ctx, paused)
+    }
+
+    pub fn freeze_market(ctx: Context<FreezeMarket>, frozen: bool) -> Result<()> {
+        instructions::freeze_market::handler(ctx, frozen)
+    }
+
+    pub fn finalize_market(ctx: Context<FinalizeMarket>) -> Result<()> {
+        instructions::finalize_market::handler(ctx)
+    }
+
+    pub fn set_crank_reward(ctx: Context<SetCrankReward>, crank_reward_bps: u16) -> Result<()> {
+        instructions::set_crank_reward::handler(ctx, crank_reward_bps)
+    }
+
+    pub fn distribute<'info>(ctx: Context<'_, '_, 'info, 'info, Distribute<'info>>) -> Result<()> {
+        instructions::distribute::handler(ctx)
+    }
+
+    pub fn migrate_protocol(ctx: Context<MigrateProtocol>) -> Result<()> {
+        instructions::migrate_protocol::handler(ctx)
+    }
+
+    pub fn migrate_market(ctx: Context<MigrateMarket>) -> Result<()> {
+        instructions::migrate_market::handler(ctx)
+    }
+
+    pub fn migrate_bet(ctx: Context<MigrateBet>) -> Result<()> {
+        instructions::migrate_bet::handler(ctx)
+    }
+
+    pub fn migrate_reputation(ctx: Context<MigrateReputation>) -> Result<()> {
+        instructions::migrate_reputation::handler(ctx)
+    }
 }
@@ -0,0 +1,160 @@
+use anchor_lang::prelude::*;
+use anchor_lang::{system_program, Discriminator};
+use crate::errors::ClawBetsError;
+use crate::state::MarketStatus;
+
+/// `Protocol` as first deployed, before fees, disputes and roles.
+#[derive(AnchorDeserialize, InitSpace)]
+pub struct ProtocolV0 {
+    pub admin: Pubkey,
+    pub market_count: u64,
+    pub total_volume: u64,
+    pub bump: u8,
+}
+
+/// `Market` as first deployed: binary, SOL-only and scaled by its feed's exponent.
+#[derive(AnchorDeserialize, InitSpace)]
+pub struct MarketV0 {
+    pub creator: Pubkey,
+    pub market_id: u64,
+    #[max_len(128)]
+    pub title: String,
+    #[max_len(512)]
+    pub description: String,
+    pub feed_id: [u8; 32],
+    pub target_price: i64,
+    pub target_above: bool,
+    pub deadline: i64,
+    pub resolution_deadline: i64,
+    pub min_bet: u64,
+    pub max_bet: u64,
+    pub total_yes: u64,
+    pub total_no: u64,
+    pub yes_count: u32,
+    pub no_count: u32,
+    /// The original statuses are the first five of the current enum
+    pub status: MarketStatus,
+    pub outcome: Option<bool>,
+    pub resolved_price: Option<i64>,
+    pub resolved_at: Option<i64>,
+    pub created_at: i64,
+    pub bump: u8,
+    pub vault_bump: u8,
+}
+
+/// `Bet` as first deployed. Claimed bets were kept open with `claimed` set.
+#[derive(AnchorDeserialize, InitSpace)]
+pub struct BetV0 {
+    pub bettor: Pubkey,
+    pub market: Pubkey,
+    pub amount: u64,
+    pub position: bool,
+    pub claimed: bool,
+    pub placed_at: i64,
+    pub bump: u8,
+}
+
+/// `AgentReputation` before creator fees were tracked.
+#[derive(AnchorDeserialize, InitSpace)]
+pub struct AgentReputationV0 {
+    pub agent: Pubkey,
+    pub total_bets: u32,
+    pub wins: u32,
+    pub losses: u32,
+    pub total_wagered: u64,
+    pub total_won: u64,
+    pub total_lost: u64,
+    pub markets_created: u32,
+    pub accuracy_bps: u16,
+    pub last_active: i64,
+    pub bump: u8,
+}
+
+/// Read an account still on a legacy layout: owned by this program, tagged with
+/// `T`'s discriminator and sized for `V`. Accounts already migrated are rejected.
+pub fn load_legacy<T: Discriminator, V: AnchorDeserialize + Space>(account: &AccountInfo) -> Result<V> {
+    require_keys_eq!(*account.owner, crate::ID, ClawBetsError::NotMigratable);
+    let data = account.try_borrow_data()?;
+    require!(data.starts_with(T::DISCRIMINATOR), ClawBetsError::NotMigratable);
+    require!(data.len() == 8 + V::INIT_SPACE, ClawBetsError::AlreadyMigrated);
+    Ok(V::deserialize(&mut &data[8..])?)
+}
+
+/// Grow `account` to `8 + T::INIT_SPACE`, topping its rent up from `payer`, and
+/// write `value` over it in the current layout.
+pub fn store_migrated<'info, T: AccountSerialize + Space>(
+    account: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    value: &T,
+) -> Result<()> {
+    let space = 8 + T::INIT_SPACE;
+    let top_up = Rent::get()?.minimum_balance(space).saturating_sub(account.lamports());
+    if top_up > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                system_program.clone(),
+                system_program::Transfer {
+                    from: payer.clone(),
+                    to: account.clone(),
+                },
+            ),
+            top_up,
+        )?;
+    }
+    account.resize(space)?;
+    value.try_serialize(&mut &mut account.try_borrow_mut_data()?[..])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::Protocol;
+
+    fn with_account<R>(owner: Pubkey, mut data: Vec<u8>, f: impl FnOnce(&AccountInfo) -> R) -> R {
+        let (key, mut lamports) = (Pubkey::new_unique(), 0);
+        let account = AccountInfo::new(&key, false, true, &mut lamports, &mut data, &owner, false, 0);
+        f(&account)
+    }
+
+    fn legacy_protocol() -> Vec<u8> {
+        let mut data = Protocol::DISCRIMINATOR.to_vec();
+        data.extend_from_slice(Pubkey::new_from_array([7; 32]).as_ref());
+        data.extend_from_slice(&3u64.to_le_bytes());
+        data.extend_from_slice(&500u64.to_le_bytes());
+        data.push(254);
+        data
+    }
+
+    #[test]
+    fn loads_a_legacy_protocol() {
+        let legacy = with_account(crate::ID, legacy_protocol(), |account| {
+            load_legacy::<Protocol, ProtocolV0>(account).unwrap()
+        });
+        assert_eq!(legacy.admin, Pubkey::new_from_array([7; 32]));
+        assert_eq!((legacy.market_count, legacy.total_volume, legacy.bump), (3, 500, 254));
+    }
+
+    #[test]
+    fn legacy_sizes_match_the_original_deployment() {
+        assert_eq!(ProtocolV0::INIT_SPACE, 49);
+        assert_eq!(MarketV0::INIT_SPACE, 816);
+        assert_eq!(BetV0::INIT_SPACE, 83);
+        assert_eq!(AgentReputationV0::INIT_SPACE, 83);
+    }
+
+    #[test]
+    fn rejects_migrated_and_foreign_accounts() {
+        let mut migrated = legacy_protocol();
+        migrated.resize(8 + Protocol::INIT_SPACE, 0);
+        let err = with_account(crate::ID, migrated, |account| {
+            load_legacy::<Protocol, ProtocolV0>(account).err().unwrap()
+        });
+        assert_eq!(err, ClawBetsError::AlreadyMigrated.into());
+
+        let err = with_account(Pubkey::new_unique(), legacy_protocol(), |account| {
+            load_legacy::<Protocol, ProtocolV0>(account).err().unwrap()
+        });
+        assert_eq!(err, ClawBetsError::NotMigratable.into());
+    }
+}
@@ -0,0 +1,1021 @@
+use anchor_lang::prelude::*;
+use pyth_solana_receiver_sdk::price_update::{Price, PriceUpdateV2, VerificationLevel};
+use std::cmp::Ordering;
+use crate::errors::ClawBetsError;
+use crate::state::{FeedConfig, Market, MarketKind, Observations, PriceSnapshot, MAX_OBSERVATIONS};
+
+/// Most negative exponent accepted for a market or an oracle price.
+pub const MIN_EXPONENT: i32 = -18;
+/// Largest exponent accepted for a market or an oracle price.
+pub const MAX_EXPONENT: i32 = 0;
+
+pub fn validate_exponent(exponent: i32) -> Result<()> {
+    require!(
+        (MIN_EXPONENT..=MAX_EXPONENT).contains(&exponent),
+        ClawBetsError::InvalidExponent
+    );
+    Ok(())
+}
+
+/// Rescale `value * 10^from_exponent` to an integer in units of `10^to_exponent`.
+/// `to_exponent` must not be larger than `from_exponent`, so the result is exact.
+pub fn rescale(value: i64, from_exponent: i32, to_exponent: i32) -> Result<i128> {
+    validate_exponent(from_exponent)?;
+    validate_exponent(to_exponent)?;
+    require!(to_exponent <= from_exponent, ClawBetsError::InvalidExponent);
+
+    let factor = 10i128
+        .checked_pow((from_exponent - to_exponent) as u32)
+        .ok_or(ClawBetsError::Overflow)?;
+    (value as i128)
+        .checked_mul(factor)
+        .ok_or(ClawBetsError::Overflow.into())
+}
+
+/// Compare an oracle price against a target, each with its own exponent, after
+/// rescaling both to the finer of the two scales.
+pub fn compare_to_target(
+    price: i64,
+    price_exponent: i32,
+    target: i64,
+    target_exponent: i32,
+) -> Result<Ordering> {
+    let common = price_exponent.min(target_exponent);
+    let price = rescale(price, price_exponent, common)?;
+    let target = rescale(target, target_exponent, common)?;
+    Ok(price.cmp(&target))
+}
+
+/// Check that a price update is the settlement price for `deadline`: the first
+/// update published at or after the deadline (`prev_publish_time < deadline <= publish_time`)
+/// and no later than `deadline + window`. Only one update satisfies this, so every
+/// resolver settles on the same price.
+pub fn check_settlement_time(
+    publish_time: i64,
+    prev_publish_time: i64,
+    deadline: i64,
+    window: i64,
+) -> Result<()> {
+    let latest = deadline.checked_add(window).ok_or(ClawBetsError::Overflow)?;
+    require!(
+        prev_publish_time < deadline && publish_time >= deadline && publish_time <= latest,
+        ClawBetsError::PublishTimeOutsideWindow
+    );
+    Ok(())
+}
+
+/// A whitelisted feed must be enabled and publish at the market's exponent.
+pub fn check_feed(feed: &FeedConfig, exponent: i32) -> Result<()> {
+    require!(feed.enabled, ClawBetsError::FeedDisabled);
+    require!(feed.exponent == exponent, ClawBetsError::FeedExponentMismatch);
+    Ok(())
+}
+
+/// Reject a settlement price published more than `max_staleness` seconds after the deadline.
+pub fn check_staleness(publish_time: i64, deadline: i64, max_staleness: i64) -> Result<()> {
+    let latest = deadline.checked_add(max_staleness).ok_or(ClawBetsError::Overflow)?;
+    require!(publish_time <= latest, ClawBetsError::PriceTooStale);
+    Ok(())
+}
+
+/// Express `value * 10^from_exponent` in units of `10^to_exponent`, rounding down
+/// when the target scale is coarser.
+pub fn to_units(value: i64, from_exponent: i32, to_exponent: i32) -> Result<i128> {
+    if to_exponent <= from_exponent {
+        return rescale(value, from_exponent, to_exponent);
+    }
+    validate_exponent(from_exponent)?;
+    validate_exponent(to_exponent)?;
+    let factor = 10i128.pow((to_exponent - from_exponent) as u32);
+    Ok((value as i128).div_euclid(factor))
+}
+
+/// Whether the confidence band is wider than `max_conf_bps` of the price.
+pub fn is_confidence_too_wide(price: i64, conf: u64, max_conf_bps: u16) -> Result<bool> {
+    let conf_limit = (price.unsigned_abs() as u128)
+        .checked_mul(max_conf_bps as u128)
+        .ok_or(ClawBetsError::Overflow)?;
+    let conf_scaled = (conf as u128)
+        .checked_mul(10000)
+        .ok_or(ClawBetsError::Overflow)?;
+    Ok(conf_scaled > conf_limit)
+}
+
+/// Whether the oracle is too uncertain to pick a side: either its confidence band
+/// is wider than `max_conf_bps` of the price, or the target lies inside the band
+/// (`|price - target| < conf`).
+pub fn is_too_close_to_call(
+    price: i64,
+    conf: u64,
+    exponent: i32,
+    target: i64,
+    target_exponent: i32,
+    max_conf_bps: u16,
+) -> Result<bool> {
+    if is_confidence_too_wide(price, conf, max_conf_bps)? {
+        return Ok(true);
+    }
+
+    let common = exponent.min(target_exponent);
+    let conf = i64::try_from(conf).map_err(|_| ClawBetsError::InvalidOracleData)?;
+    let price = rescale(price, exponent, common)?;
+    let conf = rescale(conf, exponent, common)?;
+    let target = rescale(target, target_exponent, common)?;
+    let distance = price.checked_sub(target).ok_or(ClawBetsError::Overflow)?.unsigned_abs();
+    Ok(distance < conf.unsigned_abs())
+}
+
+/// Result of settling a market against its oracle price.
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum Settlement {
+    /// Winning side (true = YES)
+    Outcome(bool),
+    /// Winning bucket of a categorical market
+    Bucket(u8),
+    /// Clamped offset of the price above a scalar market's floor
+    Scalar(u64),
+    /// Too close to call — every bet is refunded
+    Void,
+}
+
+/// Read `feed_id` from a fully verified price update.
+fn feed_price(feed_id: &[u8; 32], price_update: &PriceUpdateV2) -> Result<Price> {
+    require!(
+        price_update.verification_level == VerificationLevel::Full,
+        ClawBetsError::InvalidOracleData
+    );
+    price_update
+        .get_price_unchecked(feed_id)
+        .map_err(|_| ClawBetsError::FeedMismatch.into())
+}
+
+/// Read the market's feed from a fully verified price update.
+fn verified_price(market: &Market, price_update: &PriceUpdateV2) -> Result<Price> {
+    feed_price(&market.feed_id, price_update)
+}
+
+impl Settlement {
+    /// Human-readable outcome for logs.
+    pub fn describe(&self) -> String {
+        match self {
+            Settlement::Outcome(true) => "YES wins".to_string(),
+            Settlement::Outcome(false) => "NO wins".to_string(),
+            Settlement::Bucket(bucket) => format!("bucket {} wins", bucket),
+            Settlement::Scalar(offset) => format!("{} above floor", offset),
+            Settlement::Void => "void — too close to call".to_string(),
+        }
+    }
+}
+
+/// Validate a Pyth price update against the market and decide the outcome.
+pub fn settle(market: &Market, price_update: &PriceUpdateV2) -> Result<(Price, Settlement)> {
+    // Validates verification level and feed_id
+    let price = verified_price(market, price_update)?;
+
+    // Settle on the price published at the deadline, not whenever the resolver shows up
+    check_settlement_time(
+        price.publish_time,
+        price_update.price_message.prev_publish_time,
+        market.deadline,
+        market.price_window,
+    )?;
+
+    let settlement = settle_price(market, &price)?;
+    Ok((price, settlement))
+}
+
+/// Decide the outcome of a market from an already validated settlement price.
+pub fn settle_price(market: &Market, price: &Price) -> Result<Settlement> {
+    match &market.kind {
+        MarketKind::Binary => settle_binary(market, price),
+        MarketKind::Categorical { bounds } => settle_categorical(market, price, bounds),
+        MarketKind::Scalar { floor, cap } => settle_scalar(market, price, *floor, *cap),
+        // Touched barriers resolve from their touch proof, so this is the untouched case
+        MarketKind::Barrier => Ok(Settlement::Outcome(false)),
+        // Needs a price per feed — see settle_relative
+        MarketKind::Relative { .. } => err!(ClawBetsError::MissingFeedPrice),
+        // Resolved by proposal and dispute, not by the oracle
+        MarketKind::Optimistic { .. } => err!(ClawBetsError::InvalidMarketKind),
+    }
+}
+
+pub fn snapshot(price: &Price) -> PriceSnapshot {
+    PriceSnapshot {
+        price: price.price,
+        exponent: price.exponent,
+        publish_time: price.publish_time,
+    }
+}
+
+/// Compare the percentage return of feed A against feed B, each measured from its
+/// own start price. Returns are compared as `end_a * start_b` vs `end_b * start_a`
+/// so no division or rounding is involved; exponents are reconciled by rescaling.
+pub fn compare_returns(
+    start_a: &PriceSnapshot,
+    end_a: &PriceSnapshot,
+    start_b: &PriceSnapshot,
+    end_b: &PriceSnapshot,
+) -> Result<Ordering> {
+    require!(
+        start_a.price > 0 && end_a.price > 0 && start_b.price > 0 && end_b.price > 0,
+        ClawBetsError::InvalidOracleData
+    );
+    for exponent in [start_a.exponent, end_a.exponent, start_b.exponent, end_b.exponent] {
+        validate_exponent(exponent)?;
+    }
+
+    let mut left = (end_a.price as i128) * (start_b.price as i128);
+    let mut right = (end_b.price as i128) * (start_a.price as i128);
+    let left_exponent = end_a.exponent + start_b.exponent;
+    let right_exponent = end_b.exponent + start_a.exponent;
+    let scale = |value: i128, diff: i32| -> Result<i128> {
+        let factor = 10i128.checked_pow(diff as u32).ok_or(ClawBetsError::Overflow)?;
+        value.checked_mul(factor).ok_or(ClawBetsError::Overflow.into())
+    };
+    if left_exponent > right_exponent {
+        left = scale(left, left_exponent - right_exponent)?;
+    } else {
+        right = scale(right, right_exponent - left_exponent)?;
+    }
+    Ok(left.cmp(&right))
+}
+
+/// Validate a pair of start prices for a relative market: one per feed, each
+/// published within `price_window` of market creation.
+pub fn check_start_prices(
+    market: &Market,
+    price_update_a: &PriceUpdateV2,
+    price_update_b: &PriceUpdateV2,
+) -> Result<(Price, Price)> {
+    let feed_b = market.feed_b().ok_or(ClawBetsError::InvalidMarketKind)?;
+    let price_a = feed_price(&market.feed_id, price_update_a)?;
+    let price_b = feed_price(&feed_b, price_update_b)?;
+    for price in [&price_a, &price_b] {
+        require!(price.price > 0, ClawBetsError::InvalidOracleData);
+        let offset = price
+            .publish_time
+            .checked_sub(market.created_at)
+            .ok_or(ClawBetsError::Overflow)?;
+        require!(
+            offset.abs() <= market.price_window,
+            ClawBetsError::PublishTimeOutsideWindow
+        );
+    }
+    Ok((price_a, price_b))
+}
+
+/// Settle a relative market on the settlement price of each feed. Voids if either
+/// confidence band is too wide or the returns are exactly equal.
+pub fn settle_relative(
+    market: &Market,
+    price_update_a: &PriceUpdateV2,
+    price_update_b: &PriceUpdateV2,
+) -> Result<(Price, Price, Settlement)> {
+    let feed_b = market.feed_b().ok_or(ClawBetsError::InvalidMarketKind)?;
+    let start = market.start_prices.ok_or(ClawBetsError::StartPricesMissing)?;
+
+    let settlement_price = |feed_id: &[u8; 32], price_update: &PriceUpdateV2| -> Result<Price> {
+        let price = feed_price(feed_id, price_update)?;
+        check_settlement_time(
+            price.publish_time,
+            price_update.price_message.prev_publish_time,
+            market.deadline,
+            market.price_window,
+        )?;
+        Ok(price)
+    };
+    let price_a = settlement_price(&market.feed_id, price_update_a)?;
+    let price_b = settlement_price(&feed_b, price_update_b)?;
+
+    if is_confidence_too_wide(price_a.price, price_a.conf, market.max_conf_bps)?
+        || is_confidence_too_wide(price_b.price, price_b.conf, market.max_conf_bps)?
+    {
+        return Ok((price_a, price_b, Settlement::Void));
+    }
+    let settlement = match compare_returns(&start.a, &snapshot(&price_a), &start.b, &snapshot(&price_b))? {
+        Ordering::Greater => Settlement::Outcome(true),
+        Ordering::Less => Settlement::Outcome(false),
+        Ordering::Equal => Settlement::Void,
+    };
+    Ok((price_a, price_b, settlement))
+}
+
+/// Validate a price update as the next TWAP observation. The first must be the
+/// settlement price for the deadline; later ones must follow the previous one by at
+/// most `max_gap` seconds and stay within the price window.
+pub fn check_observation(
+    market: &Market,
+    observations: &Observations,
+    price_update: &PriceUpdateV2,
+) -> Result<Price> {
+    let twap = market.twap.ok_or(ClawBetsError::InvalidMarketKind)?;
+    let price = verified_price(market, price_update)?;
+    require!(
+        observations.observations.len() < MAX_OBSERVATIONS,
+        ClawBetsError::TooManyObservations
+    );
+
+    match observations.observations.last() {
+        None => check_settlement_time(
+            price.publish_time,
+            price_update.price_message.prev_publish_time,
+            market.deadline,
+            market.price_window,
+        )?,
+        Some(last) => {
+            require!(price.exponent == observations.exponent, ClawBetsError::InvalidOracleData);
+            require!(
+                price.publish_time > last.publish_time,
+                ClawBetsError::ObservationOutOfOrder
+            );
+            require!(
+                price.publish_time - last.publish_time <= twap.max_gap,
+                ClawBetsError::ObservationGapTooLarge
+            );
+            let latest = market
+                .deadline
+                .checked_add(market.price_window)
+                .ok_or(ClawBetsError::Overflow)?;
+            require!(price.publish_time <= latest, ClawBetsError::PublishTimeOutsideWindow);
+        }
+    }
+    Ok(price)
+}
+
+/// Time-weighted average of the observations, each price held until the next one
+/// is published. Price and confidence are rounded down; the result carries the
+/// last observation's publish time.
+pub fn twap(observations: &Observations, min_observations: u16) -> Result<Price> {
+    let points = &observations.observations;
+    require!(
+        points.len() >= (min_observations as usize).max(2),
+        ClawBetsError::NotEnoughObservations
+    );
+    let first = points[0];
+    let last = points[points.len() - 1];
+
+    let mut price_sum: i128 = 0;
+    let mut conf_sum: u128 = 0;
+    for pair in points.windows(2) {
+        let elapsed = pair[1]
+            .publish_time
+            .checked_sub(pair[0].publish_time)
+            .ok_or(ClawBetsError::Overflow)?;
+        price_sum = price_sum
+            .checked_add((pair[0].price as i128) * (elapsed as i128))
+            .ok_or(ClawBetsError::Overflow)?;
+        conf_sum = conf_sum
+            .checked_add((pair[0].conf as u128) * (elapsed as u128))
+            .ok_or(ClawBetsError::Overflow)?;
+    }
+    let span = last.publish_time - first.publish_time;
+    require!(span > 0, ClawBetsError::ObservationOutOfOrder);
+
+    Ok(Price {
+        price: i64::try_from(price_sum.div_euclid(span as i128)).map_err(|_| ClawBetsError::Overflow)?,
+        conf: u64::try_from(conf_sum / span as u128).map_err(|_| ClawBetsError::Overflow)?,
+        exponent: observations.exponent,
+        publish_time: last.publish_time,
+    })
+}
+
+fn settle_binary(market: &Market, price: &Price) -> Result<Settlement> {
+    if is_too_close_to_call(
+        price.price,
+        price.conf,
+        price.exponent,
+        market.target_price,
+        market.expected_exponent,
+        market.max_conf_bps,
+    )? {
+        return Ok(Settlement::Void);
+    }
+
+    // Both sides rescaled to a common exponent first
+    let ordering = compare_to_target(
+        price.price,
+        price.exponent,
+        market.target_price,
+        market.expected_exponent,
+    )?;
+    let outcome = if market.target_above {
+        ordering != Ordering::Less
+    } else {
+        ordering == Ordering::Less
+    };
+    Ok(Settlement::Outcome(outcome))
+}
+
+/// Pick the bucket containing the price — the number of bounds at or below it.
+/// Voids if any bound lies inside the confidence band.
+fn settle_categorical(market: &Market, price: &Price, bounds: &[i64]) -> Result<Settlement> {
+    let mut bucket: u8 = 0;
+    for bound in bounds {
+        if is_too_close_to_call(
+            price.price,
+            price.conf,
+            price.exponent,
+            *bound,
+            market.expected_exponent,
+            market.max_conf_bps,
+        )? {
+            return Ok(Settlement::Void);
+        }
+        if compare_to_target(price.price, price.exponent, *bound, market.expected_exponent)?
+            != Ordering::Less
+        {
+            bucket += 1;
+        }
+    }
+    Ok(Settlement::Bucket(bucket))
+}
+
+/// Validate a price update as proof that a barrier market's target was reached: it
+/// must be published between market creation and the deadline, and be past the
+/// target by more than its confidence band.
+pub fn check_touch(market: &Market, price_update: &PriceUpdateV2) -> Result<Price> {
+    let price = verified_price(market, price_update)?;
+    require!(
+        price.publish_time >= market.created_at && price.publish_time <= market.deadline,
+        ClawBetsError::PublishTimeOutsideWindow
+    );
+    let touched = !is_too_close_to_call(
+        price.price,
+        price.conf,
+        price.exponent,
+        market.target_price,
+        market.expected_exponent,
+        market.max_conf_bps,
+    )? && {
+        let ordering = compare_to_target(
+            price.price,
+            price.exponent,
+            market.target_price,
+            market.expected_exponent,
+        )?;
+        if market.target_above {
+            ordering != Ordering::Less
+        } else {
+            ordering == Ordering::Less
+        }
+    };
+    require!(touched, ClawBetsError::BarrierNotTouched);
+    Ok(price)
+}
+
+/// Locate the price in [floor, cap], rounded down to the market's exponent and
+/// clamped to the ends. Voids only on a too-wide confidence band, since the payout
+/// is continuous in the price.
+fn settle_scalar(market: &Market, price: &Price, floor: i64, cap: i64) -> Result<Settlement> {
+    if is_confidence_too_wide(price.price, price.conf, market.max_conf_bps)? {
+        return Ok(Settlement::Void);
+    }
+    let value = to_units(price.price, price.exponent, market.expected_exponent)?;
+    let clamped = value.clamp(floor as i128, cap as i128);
+    let offset = u64::try_from(clamped - floor as i128).map_err(|_| ClawBetsError::Overflow)?;
+    Ok(Settlement::Scalar(offset))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::{Observation, StartPrices, TwapConfig};
+    use pyth_solana_receiver_sdk::price_update::PriceFeedMessage;
+
+    const FEED: [u8; 32] = [7; 32];
+    const DEADLINE: i64 = 1_000;
+
+    fn market(target_price: i64, target_above: bool) -> Market {
+        Market {
+            feed_id: FEED,
+            target_price,
+            expected_exponent: -8,
+            target_above,
+            deadline: DEADLINE,
+            price_window: 60,
+            max_conf_bps: 100,
+            ..Default::default()
+        }
+    }
+
+    fn price_update(price: i64, conf: u64, exponent: i32) -> PriceUpdateV2 {
+        PriceUpdateV2 {
+            write_authority: Pubkey::default(),
+            verification_level: VerificationLevel::Full,
+            price_message: PriceFeedMessage {
+                feed_id: FEED,
+                price,
+                conf,
+                exponent,
+                publish_time: DEADLINE,
+                prev_publish_time: DEADLINE - 1,
+                ema_price: price,
+                ema_conf: conf,
+            },
+            posted_slot: 0,
+        }
+    }
+
+    // $250.00 expressed at each exponent
+    const TARGET_E5: i64 = 25_000_000;
+    const TARGET_E8: i64 = 25_000_000_000;
+    const TARGET_E10: i64 = 2_500_000_000_000;
+
+    #[test]
+    fn same_exponent_compares_directly() {
+        assert_eq!(compare_to_target(TARGET_E8, -8, TARGET_E8, -8).unwrap(), Ordering::Equal);
+        assert_eq!(compare_to_target(TARGET_E8 + 1, -8, TARGET_E8, -8).unwrap(), Ordering::Greater);
+        assert_eq!(compare_to_target(TARGET_E8 - 1, -8, TARGET_E8, -8).unwrap(), Ordering::Less);
+    }
+
+    #[test]
+    fn equal_prices_across_exponents() {
+        for (price, expo) in [(TARGET_E5, -5), (TARGET_E8, -8), (TARGET_E10, -10)] {
+            for (target, target_expo) in [(TARGET_E5, -5), (TARGET_E8, -8), (TARGET_E10, -10)] {
+                assert_eq!(
+                    compare_to_target(price, expo, target, target_expo).unwrap(),
+                    Ordering::Equal
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn finer_oracle_price_is_not_truncated() {
+        // $250.0000000001 at -10 is above a $250.00 target at -5
+        assert_eq!(compare_to_target(TARGET_E10 + 1, -10, TARGET_E5, -5).unwrap(), Ordering::Greater);
+        // $249.9999999999 at -10 is below a $250.00 target at -8
+        assert_eq!(compare_to_target(TARGET_E10 - 1, -10, TARGET_E8, -8).unwrap(), Ordering::Less);
+    }
+
+    #[test]
+    fn coarser_oracle_price_against_finer_target() {
+        // $250.00001 at -5 vs $250.0000000001 at -10
+        assert_eq!(compare_to_target(TARGET_E5 + 1, -5, TARGET_E10 + 1, -10).unwrap(), Ordering::Greater);
+        // $250.00 at -5 vs $250.00000001 at -8
+        assert_eq!(compare_to_target(TARGET_E5, -5, TARGET_E8 + 1, -8).unwrap(), Ordering::Less);
+    }
+
+    #[test]
+    fn raw_comparison_across_scales_would_flip() {
+        // Raw comparison of $260 at -5 against $250 at -8 would say "below"
+        let price = 26_000_000;
+        assert!(price < TARGET_E8);
+        assert_eq!(compare_to_target(price, -5, TARGET_E8, -8).unwrap(), Ordering::Greater);
+    }
+
+    #[test]
+    fn extreme_values_do_not_overflow() {
+        assert_eq!(
+            compare_to_target(i64::MAX, MAX_EXPONENT, i64::MAX, MIN_EXPONENT).unwrap(),
+            Ordering::Greater
+        );
+        assert_eq!(
+            compare_to_target(i64::MIN, MAX_EXPONENT, i64::MIN, MIN_EXPONENT).unwrap(),
+            Ordering::Less
+        );
+    }
+
+    #[test]
+    fn rejects_out_of_range_exponents() {
+        assert!(validate_exponent(MIN_EXPONENT - 1).is_err());
+        assert!(validate_exponent(MAX_EXPONENT + 1).is_err());
+        assert!(compare_to_target(1, -19, 1, -8).is_err());
+        assert!(rescale(1, -10, -5).is_err());
+    }
+
+    #[test]
+    fn settlement_time_accepts_first_update_after_deadline() {
+        assert!(check_settlement_time(1_000, 999, 1_000, 60).is_ok());
+        assert!(check_settlement_time(1_060, 990, 1_000, 60).is_ok());
+    }
+
+    #[test]
+    fn staleness_bounds_publish_time_after_deadline() {
+        assert!(check_staleness(1_000, 1_000, 30).is_ok());
+        assert!(check_staleness(1_030, 1_000, 30).is_ok());
+        assert!(check_staleness(1_031, 1_000, 30).is_err());
+    }
+
+    #[test]
+    fn feed_must_be_enabled_with_matching_exponent() {
+        let mut feed = FeedConfig {
+            feed_id: FEED,
+            symbol: "SOL/USD".to_string(),
+            exponent: -8,
+            max_staleness: 30,
+            enabled: true,
+            bump: 0,
+        };
+        assert!(check_feed(&feed, -8).is_ok());
+        assert!(check_feed(&feed, -6).is_err());
+        feed.enabled = false;
+        assert!(check_feed(&feed, -8).is_err());
+    }
+
+    #[test]
+    fn settlement_time_rejects_early_late_and_superseded_updates() {
+        // Published before the deadline
+        assert!(check_settlement_time(999, 998, 1_000, 60).is_err());
+        // Published after the window
+        assert!(check_settlement_time(1_061, 990, 1_000, 60).is_err());
+        // A later update when an earlier one already covered the deadline
+        assert!(check_settlement_time(1_005, 1_002, 1_000, 60).is_err());
+    }
+
+    #[test]
+    fn settle_picks_side_outside_confidence_band() {
+        let update = price_update(TARGET_E8 + 2_000_000, 1_000_000, -8);
+        let (_, settlement) = settle(&market(TARGET_E8, true), &update).unwrap();
+        assert_eq!(settlement, Settlement::Outcome(true));
+        let (_, settlement) = settle(&market(TARGET_E8, false), &update).unwrap();
+        assert_eq!(settlement, Settlement::Outcome(false));
+    }
+
+    #[test]
+    fn settle_voids_when_target_inside_confidence_band() {
+        let update = price_update(TARGET_E8 + 999_999, 1_000_000, -8);
+        let (_, settlement) = settle(&market(TARGET_E8, true), &update).unwrap();
+        assert_eq!(settlement, Settlement::Void);
+        // Exactly one conf away is still decidable
+        let update = price_update(TARGET_E8 - 1_000_000, 1_000_000, -8);
+        let (_, settlement) = settle(&market(TARGET_E8, true), &update).unwrap();
+        assert_eq!(settlement, Settlement::Outcome(false));
+    }
+
+    #[test]
+    fn settle_voids_when_confidence_ratio_too_wide() {
+        // conf is 2% of price, market allows 1%
+        let update = price_update(2 * TARGET_E8, 1_000_000_000, -8);
+        let (_, settlement) = settle(&market(TARGET_E8, true), &update).unwrap();
+        assert_eq!(settlement, Settlement::Void);
+    }
+
+    #[test]
+    fn settle_rescales_confidence_band() {
+        // $250.005 ± $0.01 at -5 against a $250.00 target at -8
+        let update = price_update(TARGET_E5 + 500, 1_000, -5);
+        let (_, settlement) = settle(&market(TARGET_E8, true), &update).unwrap();
+        assert_eq!(settlement, Settlement::Void);
+        // $250.02 ± $0.01 at -10
+        let update = price_update(TARGET_E10 + 200_000_000, 100_000_000, -10);
+        let (_, settlement) = settle(&market(TARGET_E8, true), &update).unwrap();
+        assert_eq!(settlement, Settlement::Outcome(true));
+    }
+
+    #[test]
+    fn settle_rejects_wrong_feed_and_partial_verification() {
+        let mut update = price_update(TARGET_E8, 0, -8);
+        update.price_message.feed_id = [8; 32];
+        assert!(settle(&market(TARGET_E8, true), &update).is_err());
+
+        let mut update = price_update(TARGET_E8, 0, -8);
+        update.verification_level = VerificationLevel::Partial { num_signatures: 5 };
+        assert!(settle(&market(TARGET_E8, true), &update).is_err());
+    }
+
+    #[test]
+    fn settle_rejects_update_outside_window() {
+        let mut update = price_update(TARGET_E8, 0, -8);
+        update.price_message.publish_time = DEADLINE + 61;
+        assert!(settle(&market(TARGET_E8, true), &update).is_err());
+    }
+
+    fn categorical_market() -> Market {
+        // <$200, $200–250, $250–300, >=$300
+        Market {
+            kind: MarketKind::Categorical {
+                bounds: vec![20_000_000_000, TARGET_E8, 30_000_000_000],
+            },
+            ..market(0, true)
+        }
+    }
+
+    #[test]
+    fn categorical_selects_bucket_containing_price() {
+        let market = categorical_market();
+        for (price, bucket) in [
+            (15_000_000_000, 0),
+            (20_000_000_000, 1),
+            (TARGET_E8 - 1_000, 1),
+            (TARGET_E8, 2),
+            (29_999_999_000, 2),
+            (35_000_000_000, 3),
+        ] {
+            let (_, settlement) = settle(&market, &price_update(price, 0, -8)).unwrap();
+            assert_eq!(settlement, Settlement::Bucket(bucket));
+        }
+    }
+
+    #[test]
+    fn categorical_rescales_price_to_bounds() {
+        // $260.00 at -5 falls in the $250–300 bucket
+        let (_, settlement) = settle(&categorical_market(), &price_update(26_000_000, 0, -5)).unwrap();
+        assert_eq!(settlement, Settlement::Bucket(2));
+    }
+
+    #[test]
+    fn categorical_voids_when_bound_inside_confidence_band() {
+        let update = price_update(TARGET_E8 + 500_000, 1_000_000, -8);
+        let (_, settlement) = settle(&categorical_market(), &update).unwrap();
+        assert_eq!(settlement, Settlement::Void);
+    }
+
+    fn scalar_market() -> Market {
+        // $200–$300
+        Market {
+            kind: MarketKind::Scalar {
+                floor: 20_000_000_000,
+                cap: 30_000_000_000,
+            },
+            ..market(0, true)
+        }
+    }
+
+    #[test]
+    fn scalar_offset_is_clamped_to_range() {
+        let market = scalar_market();
+        for (price, offset) in [
+            (15_000_000_000, 0),
+            (20_000_000_000, 0),
+            (TARGET_E8, 5_000_000_000),
+            (30_000_000_000, 10_000_000_000),
+            (45_000_000_000, 10_000_000_000),
+        ] {
+            let (_, settlement) = settle(&market, &price_update(price, 0, -8)).unwrap();
+            assert_eq!(settlement, Settlement::Scalar(offset));
+        }
+    }
+
+    #[test]
+    fn scalar_rounds_finer_price_down_to_market_units() {
+        let market = Market {
+            expected_exponent: -2,
+            kind: MarketKind::Scalar { floor: 20_000, cap: 30_000 },
+            ..scalar_market()
+        };
+        // $250.009 at -5 is 25000 cents
+        let (_, settlement) = settle(&market, &price_update(25_000_900, 0, -5)).unwrap();
+        assert_eq!(settlement, Settlement::Scalar(5_000));
+        // $260 at -1 rescales exactly
+        let (_, settlement) = settle(&market, &price_update(2_600, 0, -1)).unwrap();
+        assert_eq!(settlement, Settlement::Scalar(6_000));
+    }
+
+    #[test]
+    fn scalar_voids_only_on_wide_confidence() {
+        let market = scalar_market();
+        // Wide band relative to the range but within 1% of price
+        let (_, settlement) = settle(&market, &price_update(TARGET_E8, 200_000_000, -8)).unwrap();
+        assert_eq!(settlement, Settlement::Scalar(5_000_000_000));
+        let (_, settlement) = settle(&market, &price_update(TARGET_E8, 300_000_000, -8)).unwrap();
+        assert_eq!(settlement, Settlement::Void);
+    }
+
+    fn barrier_market(target_above: bool) -> Market {
+        Market {
+            kind: MarketKind::Barrier,
+            created_at: DEADLINE - 500,
+            ..market(TARGET_E8, target_above)
+        }
+    }
+
+    fn touch_update(price: i64, conf: u64, publish_time: i64) -> PriceUpdateV2 {
+        let mut update = price_update(price, conf, -8);
+        update.price_message.publish_time = publish_time;
+        update.price_message.prev_publish_time = publish_time - 1;
+        update
+    }
+
+    #[test]
+    fn touch_accepts_breach_in_either_direction() {
+        let price = check_touch(&barrier_market(true), &touch_update(TARGET_E8, 0, DEADLINE - 100)).unwrap();
+        assert_eq!(price.price, TARGET_E8);
+        assert!(check_touch(&barrier_market(false), &touch_update(TARGET_E8 - 1, 0, DEADLINE)).is_ok());
+    }
+
+    #[test]
+    fn touch_rejects_price_short_of_barrier_or_inside_band() {
+        assert!(check_touch(&barrier_market(true), &touch_update(TARGET_E8 - 1, 0, DEADLINE - 100)).is_err());
+        assert!(check_touch(&barrier_market(false), &touch_update(TARGET_E8, 0, DEADLINE - 100)).is_err());
+        // Barrier inside the confidence band
+        assert!(check_touch(&barrier_market(true), &touch_update(TARGET_E8 + 10, 1_000, DEADLINE - 100)).is_err());
+    }
+
+    #[test]
+    fn touch_rejects_prices_outside_market_lifetime() {
+        let market = barrier_market(true);
+        assert!(check_touch(&market, &touch_update(TARGET_E8, 0, market.created_at - 1)).is_err());
+        assert!(check_touch(&market, &touch_update(TARGET_E8, 0, DEADLINE + 1)).is_err());
+    }
+
+    fn twap_market() -> Market {
+        Market {
+            twap: Some(TwapConfig { min_observations: 3, max_gap: 20 }),
+            ..market(TARGET_E8, true)
+        }
+    }
+
+    fn observations(points: &[(i64, u64, i64)]) -> Observations {
+        Observations {
+            exponent: -8,
+            observations: points
+                .iter()
+                .map(|&(price, conf, publish_time)| Observation { price, conf, publish_time })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn twap_weights_each_price_by_time_held() {
+        // 100 held for 10s, 400 held for 30s
+        let obs = observations(&[(100, 10, 1_000), (400, 2, 1_010), (9_999, 99, 1_040)]);
+        let price = twap(&obs, 3).unwrap();
+        assert_eq!(price.price, (100 * 10 + 400 * 30) / 40);
+        assert_eq!(price.conf, (10 * 10 + 2 * 30) / 40);
+        assert_eq!(price.publish_time, 1_040);
+    }
+
+    #[test]
+    fn twap_rounds_negative_prices_down() {
+        let obs = observations(&[(-3, 0, 1_000), (-4, 0, 1_001), (0, 0, 1_002)]);
+        assert_eq!(twap(&obs, 2).unwrap().price, -4);
+    }
+
+    #[test]
+    fn twap_requires_min_observations() {
+        let obs = observations(&[(100, 0, 1_000), (200, 0, 1_010)]);
+        assert!(twap(&obs, 3).is_err());
+        assert!(twap(&observations(&[(100, 0, 1_000)]), 0).is_err());
+    }
+
+    #[test]
+    fn first_observation_must_be_settlement_price() {
+        let market = twap_market();
+        let empty = observations(&[]);
+        assert!(check_observation(&market, &empty, &price_update(TARGET_E8, 0, -8)).is_ok());
+        let mut update = price_update(TARGET_E8, 0, -8);
+        update.price_message.prev_publish_time = DEADLINE;
+        update.price_message.publish_time = DEADLINE + 5;
+        assert!(check_observation(&market, &empty, &update).is_err());
+    }
+
+    #[test]
+    fn later_observations_must_be_ordered_and_within_gap() {
+        let market = twap_market();
+        let recorded = observations(&[(TARGET_E8, 0, DEADLINE)]);
+        let at = |publish_time: i64| {
+            let mut update = price_update(TARGET_E8, 0, -8);
+            update.price_message.publish_time = publish_time;
+            update.price_message.prev_publish_time = publish_time - 1;
+            update
+        };
+        assert!(check_observation(&market, &recorded, &at(DEADLINE + 20)).is_ok());
+        assert!(check_observation(&market, &recorded, &at(DEADLINE)).is_err());
+        assert!(check_observation(&market, &recorded, &at(DEADLINE + 21)).is_err());
+
+        let late = observations(&[(TARGET_E8, 0, DEADLINE + 50)]);
+        assert!(check_observation(&market, &late, &at(DEADLINE + 61)).is_err());
+    }
+
+    #[test]
+    fn observations_rejected_without_twap_config() {
+        let update = price_update(TARGET_E8, 0, -8);
+        assert!(check_observation(&market(TARGET_E8, true), &observations(&[]), &update).is_err());
+    }
+
+    const FEED_B: [u8; 32] = [9; 32];
+
+    fn snap(price: i64, exponent: i32) -> PriceSnapshot {
+        PriceSnapshot { price, exponent, publish_time: 0 }
+    }
+
+    fn relative_market() -> Market {
+        Market {
+            kind: MarketKind::Relative { feed_b: FEED_B },
+            created_at: 100,
+            start_prices: Some(StartPrices {
+                // SOL $200 at -8, ETH $4000 at -5
+                a: snap(20_000_000_000, -8),
+                b: snap(400_000_000, -5),
+            }),
+            ..market(0, true)
+        }
+    }
+
+    fn feed_update(feed_id: [u8; 32], price: i64, conf: u64, exponent: i32) -> PriceUpdateV2 {
+        let mut update = price_update(price, conf, exponent);
+        update.price_message.feed_id = feed_id;
+        update
+    }
+
+    #[test]
+    fn returns_compare_across_exponents() {
+        // A: $200 -> $220 (+10%), B: $4000 -> $4200 (+5%)
+        let ordering = compare_returns(
+            &snap(20_000_000_000, -8),
+            &snap(22_000_000_000, -8),
+            &snap(400_000_000, -5),
+            &snap(420_000_000, -5),
+        )
+        .unwrap();
+        assert_eq!(ordering, Ordering::Greater);
+        // Same +10% on both, B's end price quoted at a finer exponent
+        let ordering = compare_returns(
+            &snap(20_000_000_000, -8),
+            &snap(22_000_000_000, -8),
+            &snap(400_000_000, -5),
+            &snap(44_000_000_000, -7),
+        )
+        .unwrap();
+        assert_eq!(ordering, Ordering::Equal);
+    }
+
+    #[test]
+    fn returns_reject_non_positive_prices() {
+        assert!(compare_returns(&snap(0, -8), &snap(1, -8), &snap(1, -8), &snap(1, -8)).is_err());
+    }
+
+    #[test]
+    fn relative_settles_on_outperformance() {
+        let market = relative_market();
+        // A +5%, B -1%
+        let (_, _, settlement) = settle_relative(
+            &market,
+            &feed_update(FEED, 21_000_000_000, 0, -8),
+            &feed_update(FEED_B, 396_000_000, 0, -5),
+        )
+        .unwrap();
+        assert_eq!(settlement, Settlement::Outcome(true));
+        // A -5%, B flat
+        let (_, _, settlement) = settle_relative(
+            &market,
+            &feed_update(FEED, 19_000_000_000, 0, -8),
+            &feed_update(FEED_B, 400_000_000, 0, -5),
+        )
+        .unwrap();
+        assert_eq!(settlement, Settlement::Outcome(false));
+    }
+
+    #[test]
+    fn relative_voids_on_tie_or_wide_confidence() {
+        let market = relative_market();
+        let (_, _, settlement) = settle_relative(
+            &market,
+            &feed_update(FEED, 22_000_000_000, 0, -8),
+            &feed_update(FEED_B, 440_000_000, 0, -5),
+        )
+        .unwrap();
+        assert_eq!(settlement, Settlement::Void);
+        let (_, _, settlement) = settle_relative(
+            &market,
+            &feed_update(FEED, 22_000_000_000, 0, -8),
+            &feed_update(FEED_B, 400_000_000, 10_000_000, -5),
+        )
+        .unwrap();
+        assert_eq!(settlement, Settlement::Void);
+    }
+
+    #[test]
+    fn relative_rejects_swapped_feeds_and_missing_start() {
+        let market = relative_market();
+        let a = feed_update(FEED, 21_000_000_000, 0, -8);
+        let b = feed_update(FEED_B, 396_000_000, 0, -5);
+        assert!(settle_relative(&market, &b, &a).is_err());
+        let unstarted = Market { start_prices: None, ..relative_market() };
+        assert!(settle_relative(&unstarted, &a, &b).is_err());
+    }
+
+    #[test]
+    fn start_prices_must_be_near_creation() {
+        let market = relative_market();
+        let at = |feed_id: [u8; 32], publish_time: i64| {
+            let mut update = feed_update(feed_id, 1_000, 0, -8);
+            update.price_message.publish_time = publish_time;
+            update
+        };
+        assert!(check_start_prices(&market, &at(FEED, 40), &at(FEED_B, 160)).is_ok());
+        assert!(check_start_prices(&market, &at(FEED, 39), &at(FEED_B, 100)).is_err());
+        assert!(check_start_prices(&market, &at(FEED_B, 100), &at(FEED, 100)).is_err());
+    }
+
+    #[test]
+    fn market_settlement_round_trips() {
+        let mut categorical = categorical_market();
+        categorical.bucket_totals = vec![0; 4];
+        for settlement in [Settlement::Bucket(3), Settlement::Void] {
+            categorical.validate_settlement(settlement).unwrap();
+            categorical.apply_settlement(settlement);
+            assert_eq!(categorical.settlement(), settlement);
+        }
+        assert!(categorical.validate_settlement(Settlement::Bucket(4)).is_err());
+        assert!(categorical.validate_settlement(Settlement::Outcome(true)).is_err());
+        assert!(categorical.validate_settlement(Settlement::Scalar(0)).is_err());
+
+        let scalar = scalar_market();
+        assert!(scalar.validate_settlement(Settlement::Scalar(10_000_000_000)).is_ok());
+        assert!(scalar.validate_settlement(Settlement::Scalar(10_000_000_001)).is_err());
+        assert!(market(TARGET_E8, true).validate_settlement(Settlement::Outcome(false)).is_ok());
+    }
+}
@@ -0,0 +1,233 @@
+use anchor_lang::prelude::*;
+use crate::errors::ClawBetsError;
+
+/// `amount * bps / 10000`, rounded down.
+pub fn bps_of(amount: u64, bps: u16) -> Result<u64> {
+    let value = (amount as u128)
+        .checked_mul(bps as u128)
+        .ok_or(ClawBetsError::Overflow)?
+        / 10000;
+    u64::try_from(value).map_err(|_| ClawBetsError::Overflow.into())
+}
+
+/// Breakdown of a winning bet's claim.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Payout {
+    /// Stake returned to the bettor — less than the original on a scalar side
+    /// that settled below its stake
+    pub stake: u64,
+    /// Share of the losing pool before fees
+    pub share: u64,
+    /// Protocol fee taken from the share
+    pub protocol_fee: u64,
+    /// Creator fee taken from the share
+    pub creator_fee: u64,
+}
+
+impl Payout {
+    /// Lamports sent to the bettor.
+    pub fn winnings(&self) -> Result<u64> {
+        self.stake
+            .checked_add(self.profit())
+            .ok_or(ClawBetsError::Overflow.into())
+    }
+
+    /// Profit after fees.
+    pub fn profit(&self) -> u64 {
+        self.share - self.protocol_fee - self.creator_fee
+    }
+}
+
+/// Parimutuel payout for a winning bet: the stake plus a pro-rata share of the
+/// losing pool, with protocol and creator fees taken from the share only.
+pub fn winning_payout(
+    amount: u64,
+    winning_pool: u64,
+    losing_pool: u64,
+    protocol_fee_bps: u16,
+    creator_fee_bps: u16,
+) -> Result<Payout> {
+    // If no one bet on the winning side, there are no winners to claim.
+    // Losers should use reclaim_bet instead.
+    require!(winning_pool > 0, ClawBetsError::NoWinners);
+
+    // Share = (bet_amount / winning_pool) * losing_pool
+    // Use u128 to avoid overflow, with safe truncation check
+    let share_128 = (amount as u128)
+        .checked_mul(losing_pool as u128)
+        .ok_or(ClawBetsError::Overflow)?
+        .checked_div(winning_pool as u128)
+        .ok_or(ClawBetsError::Overflow)?;
+    let share: u64 = u64::try_from(share_128).map_err(|_| ClawBetsError::Overflow)?;
+
+    let protocol_fee = bps_of(share, protocol_fee_bps)?;
+    let creator_fee = bps_of(share, creator_fee_bps)?;
+    require!(
+        protocol_fee.checked_add(creator_fee).ok_or(ClawBetsError::Overflow)? <= share,
+        ClawBetsError::FeeTooHigh
+    );
+
+    Ok(Payout {
+        stake: amount,
+        share,
+        protocol_fee,
+        creator_fee,
+    })
+}
+
+/// Split a scalar market's `pool` between LONG and SHORT at `offset` out of `range`.
+/// LONG gets `pool * offset / range` rounded down and SHORT gets the remainder, so
+/// the two always add up to the pool. A side with no stake passes its portion to
+/// the other side.
+pub fn scalar_split(
+    long_stake: u64,
+    short_stake: u64,
+    pool: u64,
+    offset: u64,
+    range: u64,
+) -> Result<(u64, u64)> {
+    require!(range > 0 && offset <= range, ClawBetsError::InvalidScalarRange);
+    if long_stake == 0 {
+        return Ok((0, pool));
+    }
+    if short_stake == 0 {
+        return Ok((pool, 0));
+    }
+    let long_pool = (pool as u128)
+        .checked_mul(offset as u128)
+        .ok_or(ClawBetsError::Overflow)?
+        / range as u128;
+    let long_pool: u64 = u64::try_from(long_pool).map_err(|_| ClawBetsError::Overflow)?;
+    Ok((long_pool, pool - long_pool))
+}
+
+/// Payout for a bet on a scalar side: a pro-rata share of the side's pool, rounded
+/// down. Fees are taken from any profit over the stake; a bet that settles below
+/// its stake pays no fees.
+pub fn scalar_payout(
+    amount: u64,
+    side_stake: u64,
+    side_pool: u64,
+    protocol_fee_bps: u16,
+    creator_fee_bps: u16,
+) -> Result<Payout> {
+    require!(side_stake > 0, ClawBetsError::NoWinners);
+
+    let gross = (amount as u128)
+        .checked_mul(side_pool as u128)
+        .ok_or(ClawBetsError::Overflow)?
+        .checked_div(side_stake as u128)
+        .ok_or(ClawBetsError::Overflow)?;
+    let gross: u64 = u64::try_from(gross).map_err(|_| ClawBetsError::Overflow)?;
+    if gross <= amount {
+        return Ok(Payout {
+            stake: gross,
+            share: 0,
+            protocol_fee: 0,
+            creator_fee: 0,
+        });
+    }
+
+    let share = gross - amount;
+    let protocol_fee = bps_of(share, protocol_fee_bps)?;
+    let creator_fee = bps_of(share, creator_fee_bps)?;
+    require!(
+        protocol_fee.checked_add(creator_fee).ok_or(ClawBetsError::Overflow)? <= share,
+        ClawBetsError::FeeTooHigh
+    );
+
+    Ok(Payout {
+        stake: amount,
+        share,
+        protocol_fee,
+        creator_fee,
+    })
+}
+
+/// Refund for a bet on a market that pays everyone back: the stake plus a pro-rata
+/// share of early-exit penalties left in the pool.
+pub fn refund(amount: u64, total_pool: u64, penalty_pool: u64) -> Result<u64> {
+    if total_pool == 0 || penalty_pool == 0 {
+        return Ok(amount);
+    }
+    let bonus = (amount as u128)
+        .checked_mul(penalty_pool as u128)
+        .ok_or(ClawBetsError::Overflow)?
+        .checked_div(total_pool as u128)
+        .ok_or(ClawBetsError::Overflow)?;
+    let bonus: u64 = u64::try_from(bonus).map_err(|_| ClawBetsError::Overflow)?;
+    amount.checked_add(bonus).ok_or(ClawBetsError::Overflow.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fees_come_from_profit_only() {
+        // 1 SOL on YES out of 2 SOL YES, 3 SOL NO losing pool
+        let payout = winning_payout(1_000_000_000, 2_000_000_000, 3_000_000_000, 200, 100).unwrap();
+        assert_eq!(payout.share, 1_500_000_000);
+        assert_eq!(payout.protocol_fee, 30_000_000);
+        assert_eq!(payout.creator_fee, 15_000_000);
+        assert_eq!(payout.winnings().unwrap(), 1_000_000_000 + 1_455_000_000);
+    }
+
+    #[test]
+    fn no_losing_pool_means_no_fees() {
+        let payout = winning_payout(500, 1_000, 0, 1000, 500).unwrap();
+        assert_eq!(payout.share, 0);
+        assert_eq!(payout.protocol_fee + payout.creator_fee, 0);
+        assert_eq!(payout.winnings().unwrap(), 500);
+    }
+
+    #[test]
+    fn empty_winning_pool_is_rejected() {
+        assert!(winning_payout(0, 0, 1_000, 0, 0).is_err());
+    }
+
+    #[test]
+    fn refund_shares_penalty_pool_pro_rata() {
+        assert_eq!(refund(1_000, 4_000, 0).unwrap(), 1_000);
+        assert_eq!(refund(1_000, 4_000, 400).unwrap(), 1_100);
+        assert_eq!(refund(3_000, 4_000, 400).unwrap(), 3_300);
+    }
+
+    #[test]
+    fn scalar_split_is_linear_and_exhaustive() {
+        // Price at 25% of the range
+        assert_eq!(scalar_split(600, 400, 1_000, 25, 100).unwrap(), (250, 750));
+        // Clamped ends give everything to one side
+        assert_eq!(scalar_split(600, 400, 1_000, 0, 100).unwrap(), (0, 1_000));
+        assert_eq!(scalar_split(600, 400, 1_000, 100, 100).unwrap(), (1_000, 0));
+    }
+
+    #[test]
+    fn scalar_split_rounds_long_down_and_short_takes_remainder() {
+        let (long, short) = scalar_split(1, 1, 1_001, 1, 3).unwrap();
+        assert_eq!(long, 333);
+        assert_eq!(short, 668);
+    }
+
+    #[test]
+    fn scalar_split_empty_side_passes_pool_to_other() {
+        assert_eq!(scalar_split(0, 400, 400, 75, 100).unwrap(), (0, 400));
+        assert_eq!(scalar_split(600, 0, 600, 10, 100).unwrap(), (600, 0));
+        assert!(scalar_split(600, 400, 1_000, 101, 100).is_err());
+    }
+
+    #[test]
+    fn scalar_payout_charges_fees_on_profit_only() {
+        // 1 of 2 LONG tokens, LONG side splits 3
+        let payout = scalar_payout(1_000, 2_000, 3_000, 200, 100).unwrap();
+        assert_eq!(payout.share, 500);
+        assert_eq!(payout.protocol_fee + payout.creator_fee, 15);
+        assert_eq!(payout.winnings().unwrap(), 1_485);
+
+        // SHORT side splits less than its stake
+        let payout = scalar_payout(1_000, 2_000, 1_000, 200, 100).unwrap();
+        assert_eq!(payout.stake, 500);
+        assert_eq!(payout.protocol_fee + payout.creator_fee, 0);
+        assert_eq!(payout.winnings().unwrap(), 500);
+    }
+}
@@ -1,16 +1,129 @@
 use anchor_lang::prelude::*;
+use crate::errors::ClawBetsError;
+use crate::oracle::Settlement;
+use crate::{amm, payout};
 
 #[account]
 #[derive(InitSpace)]
 pub struct Protocol {
     pub admin: Pubkey,
     pub market_count: u64,
+    /// Total SOL wagered across native markets (in lamports)
     pub total_volume: u64,
     pub bump: u8,
+    /// Fee taken from winners' profit, in basis points
+    pub fee_bps: u16,
+    /// Cumulative SOL fees sent to the treasury (in lamports)
+    pub fees_collected: u64,
+    /// Treasury PDA bump seed
+    pub treasury_bump: u8,
+    /// Seconds an oracle resolution can be challenged before claims open
+    pub dispute_period: i64,
+    /// Lamports a challenger posts to overturn an oracle resolution
+    pub challenge_bond: u64,
+    /// Admin proposed by `propose_admin`, pending `accept_admin`
+    pub pending_admin: Option<Pubkey>,
+    /// Sets the protocol fee and withdraws collected fees
+    pub fee_manager: Pubkey,
+    /// Adds, updates and disables whitelisted feeds
+    pub feed_curator: Pubkey,
+    /// Pauses operation classes protocol-wide (per-market freezes stay with the admin)
+    pub pauser: Pubkey,
+    /// Overturns oracle resolutions and arbitrates optimistic disputes without their own arbiter
+    pub dispute_arbiter: Pubkey,
+    /// Operation classes currently paused (`Protocol::PAUSE_*` bits)
+    pub paused: u8,
+    /// Share of the protocol fee paid to whoever cranks `distribute`, in basis points
+    pub crank_reward_bps: u16,
 }
 
+impl Protocol {
+    /// Hard cap on `fee_bps` (10%)
+    pub const MAX_FEE_BPS: u16 = 1000;
+    /// Hard cap on `crank_reward_bps` (20% of the protocol fee)
+    pub const MAX_CRANK_REWARD_BPS: u16 = 2000;
+    /// Hard cap on a market's `creator_fee_bps` (5%)
+    pub const MAX_CREATOR_FEE_BPS: u16 = 500;
+    /// Dispute period set at initialization (1 hour)
+    pub const DEFAULT_DISPUTE_PERIOD: i64 = 3600;
+    /// Seconds after creation during which the creator can cancel a market with bets
+    pub const CANCEL_GRACE_PERIOD: i64 = 600;
+    /// Hard cap on `dispute_period` (7 days)
+    pub const MAX_DISPUTE_PERIOD: i64 = 7 * 24 * 3600;
+    /// Seconds a pending proposal or dispute waits to be settled before the market
+    /// can be expired and both bonds returned (7 days)
+    pub const ARBITRATION_TIMEOUT: i64 = 7 * 24 * 3600;
+    /// Challenge bond set at initialization (0.1 SOL)
+    pub const DEFAULT_CHALLENGE_BOND: u64 = 100_000_000;
+    /// Pauses `create_market`
+    pub const PAUSE_CREATE: u8 = 1 << 0;
+    /// Pauses `place_bet`
+    pub const PAUSE_BET: u8 = 1 << 1;
+    /// Pauses resolution: recording oracle prices, resolving, proposing and finalizing outcomes
+    pub const PAUSE_RESOLVE: u8 = 1 << 2;
+    /// Pauses `claim_winnings` and `claim_creator_fees`
+    pub const PAUSE_CLAIM: u8 = 1 << 3;
+    /// Every pausable operation class. Refunds and early exits can't be paused.
+    pub const PAUSE_ALL: u8 =
+        Self::PAUSE_CREATE | Self::PAUSE_BET | Self::PAUSE_RESOLVE | Self::PAUSE_CLAIM;
+
+    pub fn require_unpaused(&self, operation: u8) -> Result<()> {
+        require!(self.paused & operation == 0, ClawBetsError::OperationPaused);
+        Ok(())
+    }
+
+    pub fn role(&self, role: Role) -> Pubkey {
+        match role {
+            Role::FeeManager => self.fee_manager,
+            Role::FeedCurator => self.feed_curator,
+            Role::Pauser => self.pauser,
+            Role::DisputeArbiter => self.dispute_arbiter,
+        }
+    }
+
+    pub fn set_role(&mut self, role: Role, key: Pubkey) {
+        match role {
+            Role::FeeManager => self.fee_manager = key,
+            Role::FeedCurator => self.feed_curator = key,
+            Role::Pauser => self.pauser = key,
+            Role::DisputeArbiter => self.dispute_arbiter = key,
+        }
+    }
+}
+
+/// Protocol duties the admin can delegate to separate keys.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Role {
+    FeeManager,
+    FeedCurator,
+    Pauser,
+    DisputeArbiter,
+}
+
+/// Whitelisted Pyth feed that markets may be created and resolved against.
 #[account]
 #[derive(InitSpace)]
+pub struct FeedConfig {
+    /// Pyth price feed ID
+    pub feed_id: [u8; 32],
+    /// Display symbol (e.g. "SOL/USD")
+    #[max_len(16)]
+    pub symbol: String,
+    /// Exponent the feed publishes prices with
+    pub exponent: i32,
+    /// Maximum seconds between the deadline and the settlement price's publish time
+    pub max_staleness: i64,
+    /// Disabled feeds can't be used to create or resolve markets
+    pub enabled: bool,
+    pub bump: u8,
+}
+
+impl FeedConfig {
+    pub const MAX_SYMBOL_LEN: usize = 16;
+}
+
+#[account]
+#[derive(InitSpace, Default)]
 pub struct Market {
     /// Market creator
     pub creator: Pubkey,
@@ -24,7 +137,7 @@ pub struct Market {
     pub description: String,
     /// Pyth price feed ID (32 bytes, e.g. SOL/USD feed id from Pyth)
     pub feed_id: [u8; 32],
-    /// Target price (in oracle price format, scaled)
+    /// Target price (in oracle price format, scaled by 10^expected_exponent)
     pub target_price: i64,
     /// True = bet on price being ABOVE target, False = BELOW
     pub target_above: bool,
@@ -32,17 +145,17 @@ pub struct Market {
     pub deadline: i64,
     /// Unix timestamp by which market must be resolved
     pub resolution_deadline: i64,
-    /// Minimum bet amount in lamports
+    /// Minimum bet amount in lamports (or collateral base units)
     pub min_bet: u64,
-    /// Maximum bet amount in lamports
+    /// Maximum bet amount in lamports (or collateral base units)
     pub max_bet: u64,
-    /// Total SOL bet on YES
+    /// Total collateral bet on YES
     pub total_yes: u64,
-    /// Total SOL bet on NO
+    /// Total collateral bet on NO
     pub total_no: u64,
-    /// Number of YES bettors
+    /// Number of unique YES bettors
     pub yes_count: u32,
-    /// Number of NO bettors
+    /// Number of unique NO bettors
     pub no_count: u32,
     /// Market status
     pub status: MarketStatus,
@@ -58,34 +171,559 @@ pub struct Market {
     pub bump: u8,
     /// Vault bump seed
     pub vault_bump: u8,
+    /// Exponent the creator expressed target_price in (e.g. -8 for SOL/USD)
+    pub expected_exponent: i32,
+    /// Oracle exponent of resolved_price
+    pub resolved_exponent: Option<i32>,
+    /// Seconds after deadline within which the settlement price must be published
+    pub price_window: i64,
+    /// Oracle publish time of resolved_price
+    pub resolved_publish_time: Option<i64>,
+    /// Maximum oracle confidence as basis points of price before the market is voided
+    pub max_conf_bps: u16,
+    /// Creator's cut of winners' profit, in basis points
+    pub creator_fee_bps: u16,
+    /// Creator fees accrued from claims and not yet paid out (in collateral units)
+    pub creator_fees: u64,
+    /// SPL / Token-2022 collateral mint (None = native SOL)
+    pub collateral_mint: Option<Pubkey>,
+    /// Penalty on early withdrawal, in basis points of the stake
+    pub exit_penalty_bps: u16,
+    /// Early-exit penalties left in the pool for the remaining bettors
+    pub penalty_pool: u64,
+    /// Market type and its type-specific parameters
+    pub kind: MarketKind,
+    /// Stake per bucket (categorical markets only)
+    #[max_len(MAX_BUCKETS)]
+    pub bucket_totals: Vec<u64>,
+    /// Unique bettors per bucket (categorical markets only)
+    #[max_len(MAX_BUCKETS)]
+    pub bucket_counts: Vec<u32>,
+    /// Bucket containing the resolved price (categorical markets only)
+    pub winning_bucket: Option<u8>,
+    /// Resolved price minus floor, clamped to [0, cap - floor] (scalar markets only)
+    pub scalar_offset: Option<u64>,
+    /// Oracle price that breached the barrier (barrier markets only)
+    pub touch: Option<TouchProof>,
+    /// Resolve on a time-weighted average of observations instead of one price
+    pub twap: Option<TwapConfig>,
+    /// Starting prices of both feeds (relative markets only)
+    pub start_prices: Option<StartPrices>,
+    /// Bonded outcome proposal (optimistic markets only)
+    pub proposal: Option<Proposal>,
+    /// End of the dispute period of an oracle resolution
+    pub finalizes_at: Option<i64>,
+    /// Bonded challenge that overturned the oracle resolution
+    pub challenge: Option<ResolutionChallenge>,
+    /// Frozen by the admin: no bets, resolution or claims until unfrozen
+    pub frozen: bool,
+    /// Bets claimed, settled as losses or reclaimed
+    pub settled_bets: u32,
+    /// Why the market was cancelled (cancelled markets only)
+    pub cancel_reason: Option<CancelReason>,
+    /// Constant-product YES/NO pool seeded by the creator (AMM markets only)
+    pub amm: Option<AmmPool>,
+    /// Feed max staleness captured at creation, the tighter of both feeds on
+    /// relative markets, so later feed updates can't strand the market
+    pub max_staleness: i64,
+}
+
+/// Maximum number of buckets in a categorical market
+pub const MAX_BUCKETS: usize = 8;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, InitSpace, Default, Debug)]
+pub enum MarketKind {
+    /// YES/NO on the price being above or below `target_price`
+    #[default]
+    Binary,
+    /// One bucket per price range. `bounds` are strictly increasing, in units of
+    /// 10^expected_exponent; bucket i covers [bounds[i-1], bounds[i]), with the
+    /// first and last buckets open-ended.
+    Categorical {
+        #[max_len(MAX_BUCKETS - 1)]
+        bounds: Vec<i64>,
+    },
+    /// LONG (`position = true`) or SHORT on where the price lands in [floor, cap],
+    /// in units of 10^expected_exponent. The pool is split linearly between the
+    /// sides; prices outside the range clamp to the ends.
+    Scalar { floor: i64, cap: i64 },
+    /// YES if any price published between creation and the deadline reaches
+    /// `target_price` (at or above if `target_above`, below otherwise), NO if none did.
+    Barrier,
+    /// YES if `feed_id` returns more than `feed_b` between the start snapshot and
+    /// the settlement prices, NO if less. An exact tie is void.
+    Relative { feed_b: [u8; 32] },
+    /// YES/NO on a question with no price feed. After the deadline anyone can
+    /// propose the outcome by posting `bond` lamports; it stands unless disputed
+    /// with a matching bond within `challenge_period` seconds, in which case
+    /// `arbiter` (or the protocol's dispute arbiter if None) decides.
+    Optimistic {
+        bond: u64,
+        challenge_period: i64,
+        arbiter: Option<Pubkey>,
+    },
+}
+
+/// A bonded challenge to an oracle resolution. The bond is returned at
+/// finalization if the market settles as the challenger claimed, and goes to the
+/// treasury otherwise.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace, Debug)]
+pub struct ResolutionChallenge {
+    pub challenger: Pubkey,
+    /// Settlement the challenger claims — proved by a touch on barrier markets,
+    /// left to the dispute arbiter otherwise
+    pub settlement: Settlement,
+    pub bond: u64,
+    pub challenged_at: i64,
+    /// Bond escrow PDA bump seed
+    pub bond_bump: u8,
+}
+
+/// An outcome proposed for an optimistic market.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace, Debug)]
+pub struct Proposal {
+    pub proposer: Pubkey,
+    pub outcome: bool,
+    pub proposed_at: i64,
+    pub disputer: Option<Pubkey>,
+    pub disputed_at: Option<i64>,
+    /// Bond escrow PDA bump seed
+    pub bond_bump: u8,
+}
+
+/// Oracle price of one feed at a point in time.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace, Debug)]
+pub struct PriceSnapshot {
+    pub price: i64,
+    pub exponent: i32,
+    pub publish_time: i64,
+}
+
+/// Prices the returns of a relative market are measured from.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace, Debug)]
+pub struct StartPrices {
+    /// Price of `feed_id`
+    pub a: PriceSnapshot,
+    /// Price of `feed_b`
+    pub b: PriceSnapshot,
 }
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+/// The oracle price recorded by `record_touch` as proof that a barrier was reached.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace, Debug)]
+pub struct TouchProof {
+    pub price: i64,
+    pub conf: u64,
+    pub exponent: i32,
+    pub publish_time: i64,
+    /// PriceUpdateV2 account the price was read from
+    pub price_update: Pubkey,
+    /// Slot the price update was posted in
+    pub posted_slot: u64,
+    /// Who submitted the proof
+    pub recorder: Pubkey,
+    pub recorded_at: i64,
+}
+
+impl Market {
+    pub fn require_unfrozen(&self) -> Result<()> {
+        require!(!self.frozen, ClawBetsError::MarketFrozen);
+        Ok(())
+    }
+
+    /// Bets and withdrawals are accepted only while the market is open, before the
+    /// deadline, and before a barrier touch has settled the outcome.
+    pub fn require_betting_open(&self, now: i64) -> Result<()> {
+        require!(self.status == MarketStatus::Open, ClawBetsError::MarketNotOpen);
+        require!(now < self.deadline, ClawBetsError::BettingClosed);
+        require!(self.touch.is_none(), ClawBetsError::AlreadyTouched);
+        // A settled bet's account is closed; reopening betting would let it be
+        // re-created and settled twice
+        require!(self.settled_bets == 0, ClawBetsError::MarketNotOpen);
+        Ok(())
+    }
+
+    pub fn is_categorical(&self) -> bool {
+        matches!(self.kind, MarketKind::Categorical { .. })
+    }
+
+    pub fn is_scalar(&self) -> bool {
+        matches!(self.kind, MarketKind::Scalar { .. })
+    }
+
+    pub fn is_amm(&self) -> bool {
+        self.amm.is_some()
+    }
+
+    pub fn is_barrier(&self) -> bool {
+        matches!(self.kind, MarketKind::Barrier)
+    }
+
+    /// Settlement currently recorded on the market.
+    pub fn settlement(&self) -> Settlement {
+        match (self.outcome, self.winning_bucket, self.scalar_offset) {
+            (Some(outcome), _, _) => Settlement::Outcome(outcome),
+            (None, Some(bucket), _) => Settlement::Bucket(bucket),
+            (None, None, Some(offset)) => Settlement::Scalar(offset),
+            (None, None, None) => Settlement::Void,
+        }
+    }
+
+    /// Whether a challenge to the resolution stood, so its bond goes back to the
+    /// challenger. `None` if the resolution was never challenged.
+    pub fn challenge_upheld(&self) -> Option<bool> {
+        self.challenge.map(|challenge| challenge.settlement == self.settlement())
+    }
+
+    /// Record a settlement, replacing any previous one.
+    pub fn apply_settlement(&mut self, settlement: Settlement) {
+        self.outcome = None;
+        self.winning_bucket = None;
+        self.scalar_offset = None;
+        match settlement {
+            Settlement::Outcome(outcome) => self.outcome = Some(outcome),
+            Settlement::Bucket(bucket) => self.winning_bucket = Some(bucket),
+            Settlement::Scalar(offset) => self.scalar_offset = Some(offset),
+            Settlement::Void => {}
+        }
+    }
+
+    /// Check that a settlement fits this market's kind.
+    pub fn validate_settlement(&self, settlement: Settlement) -> Result<()> {
+        let valid = match (&self.kind, settlement) {
+            (_, Settlement::Void) => true,
+            (MarketKind::Categorical { .. }, Settlement::Bucket(bucket)) => {
+                (bucket as usize) < self.bucket_totals.len()
+            }
+            (MarketKind::Scalar { floor, cap }, Settlement::Scalar(offset)) => {
+                offset as i128 <= *cap as i128 - *floor as i128
+            }
+            (MarketKind::Categorical { .. } | MarketKind::Scalar { .. }, _) => false,
+            (_, Settlement::Outcome(_)) => true,
+            _ => false,
+        };
+        require!(valid, ClawBetsError::InvalidSettlement);
+        Ok(())
+    }
+
+    /// Bond and challenge period of an optimistic market.
+    pub fn optimistic_terms(&self) -> Option<(u64, i64)> {
+        match self.kind {
+            MarketKind::Optimistic { bond, challenge_period, .. } => Some((bond, challenge_period)),
+            _ => None,
+        }
+    }
+
+    /// When a pending optimistic market can be expired: a proposal the challenge
+    /// period ended on but nobody finalized, or a dispute the arbiter never decided.
+    /// `None` unless the market is Proposed or Disputed.
+    pub fn arbitration_deadline(&self) -> Result<Option<i64>> {
+        let (Some(proposal), Some((_, challenge_period))) = (self.proposal, self.optimistic_terms()) else {
+            return Ok(None);
+        };
+        let since = match self.status {
+            MarketStatus::Proposed => proposal.proposed_at.checked_add(challenge_period),
+            MarketStatus::Disputed => proposal.disputed_at,
+            _ => return Ok(None),
+        };
+        since
+            .and_then(|since| since.checked_add(Protocol::ARBITRATION_TIMEOUT))
+            .map(Some)
+            .ok_or(ClawBetsError::Overflow.into())
+    }
+
+    /// Second feed of a relative market.
+    pub fn feed_b(&self) -> Option<[u8; 32]> {
+        match self.kind {
+            MarketKind::Relative { feed_b } => Some(feed_b),
+            _ => None,
+        }
+    }
+
+    /// Total stake across every side of the market.
+    pub fn total_pool(&self) -> Result<u64> {
+        if self.is_categorical() {
+            return self
+                .bucket_totals
+                .iter()
+                .try_fold(0u64, |acc, total| acc.checked_add(*total))
+                .ok_or(ClawBetsError::Overflow.into());
+        }
+        self.total_yes.checked_add(self.total_no).ok_or(ClawBetsError::Overflow.into())
+    }
+
+    /// Add stake to the side of a bet. `is_new` counts a new unique bettor.
+    pub fn add_stake(&mut self, position: bool, bucket: u8, amount: u64, is_new: bool) -> Result<()> {
+        let added = is_new as u32;
+        if self.is_categorical() {
+            let i = bucket as usize;
+            self.bucket_totals[i] = self.bucket_totals[i].checked_add(amount).ok_or(ClawBetsError::Overflow)?;
+            self.bucket_counts[i] = self.bucket_counts[i].checked_add(added).ok_or(ClawBetsError::Overflow)?;
+        } else if position {
+            self.total_yes = self.total_yes.checked_add(amount).ok_or(ClawBetsError::Overflow)?;
+            self.yes_count = self.yes_count.checked_add(added).ok_or(ClawBetsError::Overflow)?;
+        } else {
+            self.total_no = self.total_no.checked_add(amount).ok_or(ClawBetsError::Overflow)?;
+            self.no_count = self.no_count.checked_add(added).ok_or(ClawBetsError::Overflow)?;
+        }
+        Ok(())
+    }
+
+    /// Number of live bets (withdrawn bets are no longer counted).
+    pub fn bet_count(&self) -> Result<u32> {
+        let binary = self.yes_count.checked_add(self.no_count).ok_or(ClawBetsError::Overflow)?;
+        self.bucket_counts
+            .iter()
+            .try_fold(binary, |total, count| total.checked_add(*count))
+            .ok_or(ClawBetsError::Overflow.into())
+    }
+
+    /// Whether every live bet has been claimed, settled as a loss or reclaimed.
+    pub fn is_fully_settled(&self) -> Result<bool> {
+        Ok(self.settled_bets == self.bet_count()?)
+    }
+
+    /// Count a bet as settled. Settling closes the `Bet`, and `require_betting_open`
+    /// refuses new bets once any bet is settled, so a closed bet can't be
+    /// re-created and settled twice.
+    pub fn record_settled_bet(&mut self) -> Result<()> {
+        self.settled_bets = self.settled_bets.checked_add(1).ok_or(ClawBetsError::Overflow)?;
+        Ok(())
+    }
+
+    /// Remove a whole bet's stake and its bettor from the market.
+    pub fn remove_stake(&mut self, bet: &Bet) -> Result<()> {
+        if self.is_categorical() {
+            let i = bet.bucket as usize;
+            self.bucket_totals[i] = self.bucket_totals[i].checked_sub(bet.amount).ok_or(ClawBetsError::Overflow)?;
+            self.bucket_counts[i] = self.bucket_counts[i].checked_sub(1).ok_or(ClawBetsError::Overflow)?;
+        } else if bet.position {
+            self.total_yes = self.total_yes.checked_sub(bet.amount).ok_or(ClawBetsError::Overflow)?;
+            self.yes_count = self.yes_count.checked_sub(1).ok_or(ClawBetsError::Overflow)?;
+        } else {
+            self.total_no = self.total_no.checked_sub(bet.amount).ok_or(ClawBetsError::Overflow)?;
+            self.no_count = self.no_count.checked_sub(1).ok_or(ClawBetsError::Overflow)?;
+        }
+        Ok(())
+    }
+
+    /// Whether `bet` took YES on a barrier market at or after the touch it was
+    /// resolved by, when the outcome was already known. Such bets never win and are
+    /// refunded their stake instead; the share of the losing pool they would have
+    /// earned is left to the treasury as dust.
+    pub fn is_late_bet(&self, bet: &Bet) -> bool {
+        bet.position && self.touch.is_some_and(|touch| bet.last_deposit_at >= touch.publish_time)
+    }
+
+    /// Whether the resolved outcome favours `bet`. On scalar markets any side with
+    /// a share of the pool counts, even if it is less than the stake.
+    pub fn is_winning_bet(&self, bet: &Bet) -> Result<bool> {
+        if self.is_late_bet(bet) {
+            return Ok(false);
+        }
+        if self.is_scalar() {
+            let (_, side_pool) = self.scalar_side(bet.position)?;
+            return Ok(side_pool > 0);
+        }
+        if self.is_categorical() {
+            let winner = self.winning_bucket.ok_or(ClawBetsError::MarketNotResolved)?;
+            return Ok(bet.bucket == winner);
+        }
+        let outcome = self.outcome.ok_or(ClawBetsError::MarketNotResolved)?;
+        Ok(bet.position == outcome)
+    }
+
+    /// Whether any winning bet can claim. An AMM pool always holds winning shares, so
+    /// its losing bets never get refunded.
+    pub fn has_winners(&self) -> Result<bool> {
+        Ok(self.is_amm() || self.settled_pools()?.0 > 0)
+    }
+
+    /// Collateral the creator can redeem from the AMM pool: its winning reserve once
+    /// resolved, or the seed liquidity if every bet is refunded.
+    pub fn lp_claim(&self) -> u64 {
+        let Some(pool) = self.amm else {
+            return 0;
+        };
+        if pool.redeemed {
+            return 0;
+        }
+        match self.status {
+            MarketStatus::Resolved => match self.outcome {
+                Some(true) => pool.yes_reserve,
+                Some(false) => pool.no_reserve,
+                None => 0,
+            },
+            MarketStatus::Cancelled | MarketStatus::Expired | MarketStatus::Void => pool.liquidity,
+            _ => 0,
+        }
+    }
+
+    /// Winning and losing pools for the resolved outcome (excluding the penalty pool).
+    /// Scalar markets pay out the whole pool, so none of it counts as losing.
+    pub fn settled_pools(&self) -> Result<(u64, u64)> {
+        if self.is_scalar() {
+            return Ok((self.total_pool()?, 0));
+        }
+        let winning_pool = if self.is_categorical() {
+            let winner = self.winning_bucket.ok_or(ClawBetsError::MarketNotResolved)?;
+            self.bucket_totals[winner as usize]
+        } else if self.outcome.ok_or(ClawBetsError::MarketNotResolved)? {
+            self.total_yes
+        } else {
+            self.total_no
+        };
+        Ok((winning_pool, self.total_pool()? - winning_pool))
+    }
+
+    /// Stake on a scalar side (`true` = LONG) and the part of the pool, penalties
+    /// included, that side splits at resolution.
+    pub fn scalar_side(&self, long: bool) -> Result<(u64, u64)> {
+        let MarketKind::Scalar { floor, cap } = self.kind else {
+            return err!(ClawBetsError::InvalidMarketKind);
+        };
+        let offset = self.scalar_offset.ok_or(ClawBetsError::MarketNotResolved)?;
+        let pool = self
+            .total_pool()?
+            .checked_add(self.penalty_pool)
+            .ok_or(ClawBetsError::Overflow)?;
+        let range = (cap as i128 - floor as i128) as u64;
+        let (long_pool, short_pool) =
+            payout::scalar_split(self.total_yes, self.total_no, pool, offset, range)?;
+        Ok(if long {
+            (self.total_yes, long_pool)
+        } else {
+            (self.total_no, short_pool)
+        })
+    }
+
+    /// Payout for a winning `bet`, shared by `claim_winnings` and `distribute`.
+    /// Protocol and creator fees are taken from the profit only, never the stake.
+    pub fn winning_payout(&self, bet: &Bet, protocol_fee_bps: u16) -> Result<payout::Payout> {
+        if self.is_amm() {
+            // Each winning share redeems for one unit of collateral
+            return amm::redemption(bet.shares, bet.amount, protocol_fee_bps, self.creator_fee_bps);
+        }
+        if self.is_scalar() {
+            // Pro-rata share of this side's part of the pool, penalties included
+            let (side_stake, side_pool) = self.scalar_side(bet.position)?;
+            return payout::scalar_payout(
+                bet.amount,
+                side_stake,
+                side_pool,
+                protocol_fee_bps,
+                self.creator_fee_bps,
+            );
+        }
+        // Proportional share of the losing pool + original bet. Early-exit
+        // penalties go to the winners along with the losing pool.
+        let (winning_pool, losing_pool) = self.settled_pools()?;
+        let losing_pool = losing_pool.checked_add(self.penalty_pool).ok_or(ClawBetsError::Overflow)?;
+        payout::winning_payout(
+            bet.amount,
+            winning_pool,
+            losing_pool,
+            protocol_fee_bps,
+            self.creator_fee_bps,
+        )
+    }
+}
+
+/// Maximum observations recorded for a TWAP market
+pub const MAX_OBSERVATIONS: usize = 32;
+
+/// TWAP resolution parameters. Observations are taken from the settlement price
+/// onwards, within `price_window` of the deadline.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace, Debug)]
+pub struct TwapConfig {
+    /// Observations needed before the market can resolve (at least 2)
+    pub min_observations: u16,
+    /// Maximum seconds between consecutive observations
+    pub max_gap: i64,
+}
+
+/// Price observations accumulated by `record_observation` for a TWAP market.
+#[account]
+#[derive(InitSpace, Default)]
+pub struct Observations {
+    pub market: Pubkey,
+    /// Oracle exponent shared by every observation
+    pub exponent: i32,
+    /// Observations in strictly increasing publish_time order
+    #[max_len(MAX_OBSERVATIONS)]
+    pub observations: Vec<Observation>,
+    pub bump: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace, Debug)]
+pub struct Observation {
+    pub price: i64,
+    pub conf: u64,
+    pub publish_time: i64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace, Default)]
 pub enum MarketStatus {
+    #[default]
     Open,
     Closed,     // Deadline passed, awaiting resolution
-    Resolved,   // Resolved with outcome
-    Cancelled,  // Cancelled by creator
+    Resolved,   // Resolution final — claims open
+    Cancelled,  // Cancelled by creator or admin — all bets refunded
     Expired,    // Resolution deadline passed without resolution
+    Void,       // Oracle too uncertain to pick a side — all bets refunded
+    Proposed,   // Optimistic outcome proposed, challenge period running
+    Disputed,   // Optimistic proposal disputed, awaiting the arbiter
+    Resolving,  // Oracle resolution in its dispute period — claims blocked
+}
+
+/// Reserves of an AMM market. Bets buy shares from the pool at its current price,
+/// and each winning share redeems for one unit of collateral.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace, Debug)]
+pub struct AmmPool {
+    /// YES shares held by the pool
+    pub yes_reserve: u64,
+    /// NO shares held by the pool
+    pub no_reserve: u64,
+    /// Collateral the creator seeded the pool with
+    pub liquidity: u64,
+    /// Whether the creator has redeemed the pool's shares
+    pub redeemed: bool,
+}
+
+/// Reason code recorded when a market is cancelled.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace, Debug)]
+pub enum CancelReason {
+    /// Wrong target, deadline or other market parameter
+    InvalidParameters,
+    /// Feed unavailable or unreliable for this market
+    OracleIssue,
+    /// Duplicates another market
+    Duplicate,
+    /// Misleading or disallowed market
+    Policy,
+    Other,
 }
 
 #[account]
-#[derive(InitSpace)]
+#[derive(InitSpace, Default)]
 pub struct Bet {
     /// Bettor's public key
     pub bettor: Pubkey,
     /// Market this bet belongs to
     pub market: Pubkey,
-    /// Amount in lamports
+    /// Cumulative amount in lamports (or collateral base units)
     pub amount: u64,
-    /// YES (true) or NO (false)
+    /// YES (true) or NO (false) — binary markets
     pub position: bool,
-    /// Whether winnings have been claimed
-    pub claimed: bool,
-    /// Timestamp of bet placement
+    /// Timestamp of the first deposit
     pub placed_at: i64,
     /// Bump seed
     pub bump: u8,
+    /// Bucket index — categorical markets
+    pub bucket: u8,
+    /// Outcome shares bought (AMM markets only)
+    pub shares: u64,
+    /// Timestamp of the latest deposit
+    pub last_deposit_at: i64,
 }
 
 #[account]
@@ -99,18 +737,190 @@ pub struct AgentReputation {
     pub wins: u32,
     /// Total bets lost
     pub losses: u32,
-    /// Total SOL wagered (in lamports)
+    /// Total SOL wagered on native markets (in lamports)
     pub total_wagered: u64,
-    /// Total SOL won (in lamports)
+    /// Total SOL won on native markets (in lamports)
     pub total_won: u64,
-    /// Total SOL lost (in lamports)
+    /// Total SOL lost on native markets (in lamports)
     pub total_lost: u64,
     /// Markets created
     pub markets_created: u32,
-    /// Accuracy basis points (wins * 10000 / total_bets)
+    /// Accuracy basis points (wins * 10000 / (wins + losses))
     pub accuracy_bps: u16,
     /// Last activity timestamp
     pub last_active: i64,
     /// Bump seed
     pub bump: u8,
+    /// Total SOL creator fees earned from native markets (in lamports)
+    pub creator_fees_earned: u64,
+}
+
+impl AgentReputation {
+    /// Record a claimed winning bet. A scalar side that settled below its stake
+    /// counts as a loss. SOL totals only move on native markets.
+    pub fn record_claim(&mut self, bet: &Bet, payout: &payout::Payout, is_native: bool, now: i64) -> Result<()> {
+        if payout.stake < bet.amount {
+            self.losses = self.losses.checked_add(1).ok_or(ClawBetsError::Overflow)?;
+            if is_native {
+                let lost = bet.amount - payout.stake;
+                self.total_lost = self.total_lost.checked_add(lost).ok_or(ClawBetsError::Overflow)?;
+            }
+        } else {
+            self.wins = self.wins.checked_add(1).ok_or(ClawBetsError::Overflow)?;
+            if is_native {
+                self.total_won = self.total_won.checked_add(payout.profit()).ok_or(ClawBetsError::Overflow)?;
+            }
+        }
+        self.recompute_accuracy()?;
+        self.last_active = now;
+        Ok(())
+    }
+
+    /// Recompute `accuracy_bps` from settled wins and losses.
+    pub fn recompute_accuracy(&mut self) -> Result<()> {
+        let total = self.wins.checked_add(self.losses).ok_or(ClawBetsError::Overflow)?;
+        if total > 0 {
+            self.accuracy_bps = ((self.wins as u64)
+                .checked_mul(10000)
+                .ok_or(ClawBetsError::Overflow)?
+                .checked_div(total as u64)
+                .ok_or(ClawBetsError::Overflow)?) as u16;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DEADLINE: i64 = 1_000;
+
+    fn touch() -> TouchProof {
+        TouchProof {
+            price: 25_000_000_000,
+            conf: 0,
+            exponent: -8,
+            publish_time: DEADLINE - 10,
+            price_update: Pubkey::default(),
+            posted_slot: 0,
+            recorder: Pubkey::default(),
+            recorded_at: DEADLINE - 5,
+        }
+    }
+
+    #[test]
+    fn betting_open_before_deadline() {
+        let market = Market { deadline: DEADLINE, ..Default::default() };
+        assert!(market.require_betting_open(DEADLINE - 1).is_ok());
+        assert!(market.require_betting_open(DEADLINE).is_err());
+    }
+
+    #[test]
+    fn betting_closed_after_barrier_touch() {
+        let market = Market {
+            kind: MarketKind::Barrier,
+            deadline: DEADLINE,
+            touch: Some(touch()),
+            ..Default::default()
+        };
+        // Bets and withdrawals share this gate, so neither is accepted once touched
+        assert_eq!(
+            market.require_betting_open(DEADLINE - 1).unwrap_err(),
+            ClawBetsError::AlreadyTouched.into()
+        );
+    }
+
+    #[test]
+    fn betting_closed_unless_open() {
+        let market = Market {
+            deadline: DEADLINE,
+            status: MarketStatus::Closed,
+            ..Default::default()
+        };
+        assert!(market.require_betting_open(DEADLINE - 1).is_err());
+    }
+
+    #[test]
+    fn betting_never_reopens_after_a_settled_bet() {
+        let market = Market {
+            status: MarketStatus::Open,
+            deadline: DEADLINE,
+            settled_bets: 1,
+            ..Default::default()
+        };
+        assert_eq!(
+            market.require_betting_open(DEADLINE - 1).unwrap_err(),
+            ClawBetsError::MarketNotOpen.into()
+        );
+    }
+
+    #[test]
+    fn yes_bets_after_the_touch_never_win() {
+        let market = Market {
+            kind: MarketKind::Barrier,
+            status: MarketStatus::Resolved,
+            outcome: Some(true),
+            touch: Some(touch()),
+            ..Default::default()
+        };
+        let publish_time = touch().publish_time;
+        let yes = |last_deposit_at| Bet { position: true, last_deposit_at, ..Default::default() };
+
+        assert!(market.is_winning_bet(&yes(publish_time - 1)).unwrap());
+        assert!(market.is_late_bet(&yes(publish_time)));
+        assert!(!market.is_winning_bet(&yes(publish_time)).unwrap());
+        // NO bets after the touch simply lose
+        let no = Bet { position: false, last_deposit_at: publish_time, ..Default::default() };
+        assert!(!market.is_late_bet(&no));
+    }
+
+    #[test]
+    fn arbitration_deadline_runs_from_the_dispute() {
+        let proposal = Proposal {
+            proposer: Pubkey::default(),
+            outcome: true,
+            proposed_at: 100,
+            disputer: Some(Pubkey::default()),
+            disputed_at: Some(150),
+            bond_bump: 0,
+        };
+        let mut market = Market {
+            kind: MarketKind::Optimistic { bond: 1, challenge_period: 200, arbiter: None },
+            status: MarketStatus::Disputed,
+            proposal: Some(proposal),
+            ..Default::default()
+        };
+        assert_eq!(market.arbitration_deadline().unwrap(), Some(150 + Protocol::ARBITRATION_TIMEOUT));
+
+        // An undisputed proposal can be finalized until the timeout after its challenge period
+        market.status = MarketStatus::Proposed;
+        assert_eq!(market.arbitration_deadline().unwrap(), Some(300 + Protocol::ARBITRATION_TIMEOUT));
+
+        market.status = MarketStatus::Resolved;
+        assert_eq!(market.arbitration_deadline().unwrap(), None);
+    }
+
+    #[test]
+    fn challenge_upheld_when_the_arbiter_adopts_the_claim() {
+        // A binary market resolved NO, challenged with a bonded claim that YES won
+        let mut market = Market {
+            status: MarketStatus::Resolving,
+            outcome: Some(false),
+            challenge: Some(ResolutionChallenge {
+                challenger: Pubkey::default(),
+                settlement: Settlement::Outcome(true),
+                bond: 1,
+                challenged_at: 0,
+                bond_bump: 0,
+            }),
+            ..Default::default()
+        };
+        // Unreviewed, the original resolution stands and the bond is forfeit
+        assert_eq!(market.challenge_upheld(), Some(false));
+
+        market.validate_settlement(Settlement::Outcome(true)).unwrap();
+        market.apply_settlement(Settlement::Outcome(true));
+        assert_eq!(market.challenge_upheld(), Some(true));
+    }
 }
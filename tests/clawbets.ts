@@ -20,8 +20,10 @@ describe("clawbets", () => {
   const bettor2 = Keypair.generate();
 
   let protocolPda: PublicKey;
+  let treasuryPda: PublicKey;
   let marketPda: PublicKey;
   let vaultPda: PublicKey;
+  let feedPda: PublicKey;
   let mockOracle: Keypair;
 
   let marketDeadline: number;
@@ -33,6 +35,11 @@ describe("clawbets", () => {
       program.programId
     );
 
+    [treasuryPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("treasury")],
+      program.programId
+    );
+
     [marketPda] = PublicKey.findProgramAddressSync(
       [Buffer.from("market"), new anchor.BN(0).toArrayLike(Buffer, "le", 8)],
       program.programId
@@ -66,6 +73,11 @@ describe("clawbets", () => {
     });
     const tx = new anchor.web3.Transaction().add(createIx);
     await provider.sendAndConfirm(tx, [mockOracle]);
+
+    [feedPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("feed"), mockOracle.publicKey.toBuffer()],
+      program.programId
+    );
   });
 
   it("Initializes the protocol", async () => {
@@ -74,6 +86,7 @@ describe("clawbets", () => {
       .accounts({
         admin: admin.publicKey,
         protocol: protocolPda,
+        treasury: treasuryPda,
         systemProgram: SystemProgram.programId,
       })
       .rpc();
@@ -82,6 +95,27 @@ describe("clawbets", () => {
     expect(protocol.admin.toBase58()).to.equal(admin.publicKey.toBase58());
     expect(protocol.marketCount.toNumber()).to.equal(0);
     expect(protocol.totalVolume.toNumber()).to.equal(0);
+    expect(protocol.feeBps).to.equal(0);
+    expect(protocol.pendingAdmin).to.equal(null);
+    expect(protocol.feeManager.toBase58()).to.equal(admin.publicKey.toBase58());
+    expect(protocol.feedCurator.toBase58()).to.equal(admin.publicKey.toBase58());
+  });
+
+  it("Whitelists a feed", async () => {
+    await program.methods
+      .addFeed(mockOracle.publicKey.toBytes(), "SOL/USD", -8, new anchor.BN(30))
+      .accounts({
+        feedCurator: admin.publicKey,
+        protocol: protocolPda,
+        feed: feedPda,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    const feed = await program.account.feedConfig.fetch(feedPda);
+    expect(feed.symbol).to.equal("SOL/USD");
+    expect(feed.exponent).to.equal(-8);
+    expect(feed.enabled).to.equal(true);
   });
 
   it("Creates a market", async () => {
@@ -100,11 +134,19 @@ describe("clawbets", () => {
         "Will SOL be above $250 by deadline?",
         mockOracle.publicKey,
         new anchor.BN(25000000000),
+        -8,
         true,
+        100,
         new anchor.BN(marketDeadline),
         new anchor.BN(marketResDeadline),
+        new anchor.BN(30),
         new anchor.BN(0.1 * LAMPORTS_PER_SOL),
-        new anchor.BN(5 * LAMPORTS_PER_SOL)
+        new anchor.BN(5 * LAMPORTS_PER_SOL),
+        100,
+        500,
+        { binary: {} },
+        null,
+        null
       )
       .accounts({
         creator: admin.publicKey,
@@ -112,6 +154,17 @@ describe("clawbets", () => {
         market: marketPda,
         vault: vaultPda,
         reputation: reputationPda,
+        treasury: treasuryPda,
+        startPriceUpdate: null,
+        startPriceUpdateB: null,
+        feed: feedPda,
+        feedB: null,
+        collateralMint: null,
+        vaultToken: null,
+        treasuryToken: null,
+        creatorToken: null,
+        tokenProgram: null,
+        associatedTokenProgram: null,
         systemProgram: SystemProgram.programId,
       })
       .rpc();
@@ -119,6 +172,8 @@ describe("clawbets", () => {
     const market = await program.account.market.fetch(marketPda);
     expect(market.title).to.equal("SOL above $250?");
     expect(market.status).to.deep.equal({ open: {} });
+    expect(market.creatorFeeBps).to.equal(100);
+    expect(market.maxStaleness.toNumber()).to.equal(30);
 
     const protocol = await program.account.protocol.fetch(protocolPda);
     expect(protocol.marketCount.toNumber()).to.equal(1);
@@ -137,7 +192,7 @@ describe("clawbets", () => {
     );
 
     await program.methods
-      .placeBet(new anchor.BN(betAmount), true)
+      .placeBet(new anchor.BN(betAmount), true, 0, new anchor.BN(0))
       .accounts({
         bettor: bettor1.publicKey,
         market: marketPda,
@@ -145,6 +200,10 @@ describe("clawbets", () => {
         vault: vaultPda,
         reputation: reputationPda,
         protocol: protocolPda,
+        collateralMint: null,
+        vaultToken: null,
+        bettorToken: null,
+        tokenProgram: null,
         systemProgram: SystemProgram.programId,
       })
       .signers([bettor1])
@@ -172,7 +231,7 @@ describe("clawbets", () => {
     );
 
     await program.methods
-      .placeBet(new anchor.BN(betAmount), false)
+      .placeBet(new anchor.BN(betAmount), false, 0, new anchor.BN(0))
       .accounts({
         bettor: bettor2.publicKey,
         market: marketPda,
@@ -180,6 +239,10 @@ describe("clawbets", () => {
         vault: vaultPda,
         reputation: reputationPda,
         protocol: protocolPda,
+        collateralMint: null,
+        vaultToken: null,
+        bettorToken: null,
+        tokenProgram: null,
         systemProgram: SystemProgram.programId,
       })
       .signers([bettor2])
@@ -190,6 +253,123 @@ describe("clawbets", () => {
     expect(market.noCount).to.equal(1);
   });
 
+  it("Tops up an existing YES bet", async () => {
+    const topUp = 0.5 * LAMPORTS_PER_SOL;
+
+    const [betPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("bet"), marketPda.toBuffer(), bettor1.publicKey.toBuffer()],
+      program.programId
+    );
+    const [reputationPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("reputation"), bettor1.publicKey.toBuffer()],
+      program.programId
+    );
+
+    await program.methods
+      .placeBet(new anchor.BN(topUp), true, 0, new anchor.BN(0))
+      .accounts({
+        bettor: bettor1.publicKey,
+        market: marketPda,
+        bet: betPda,
+        vault: vaultPda,
+        reputation: reputationPda,
+        protocol: protocolPda,
+        collateralMint: null,
+        vaultToken: null,
+        bettorToken: null,
+        tokenProgram: null,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([bettor1])
+      .rpc();
+
+    const bet = await program.account.bet.fetch(betPda);
+    expect(bet.amount.toNumber()).to.equal(1.5 * LAMPORTS_PER_SOL);
+
+    const market = await program.account.market.fetch(marketPda);
+    expect(market.totalYes.toNumber()).to.equal(1.5 * LAMPORTS_PER_SOL);
+    expect(market.yesCount).to.equal(1);
+  });
+
+  it("Rejects hedging an existing bet", async () => {
+    const [betPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("bet"), marketPda.toBuffer(), bettor1.publicKey.toBuffer()],
+      program.programId
+    );
+    const [reputationPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("reputation"), bettor1.publicKey.toBuffer()],
+      program.programId
+    );
+
+    try {
+      await program.methods
+        .placeBet(new anchor.BN(0.5 * LAMPORTS_PER_SOL), false, 0, new anchor.BN(0))
+        .accounts({
+          bettor: bettor1.publicKey,
+          market: marketPda,
+          bet: betPda,
+          vault: vaultPda,
+          reputation: reputationPda,
+          protocol: protocolPda,
+          collateralMint: null,
+          vaultToken: null,
+          bettorToken: null,
+          tokenProgram: null,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([bettor1])
+        .rpc();
+      expect.fail("Should have thrown");
+    } catch (err: any) {
+      expect(err.error.errorCode.code).to.equal("HedgingNotAllowed");
+    }
+  });
+
+  it("Rejects bets while betting is paused", async () => {
+    const PAUSE_BET = 1 << 1;
+    await program.methods
+      .setPaused(PAUSE_BET)
+      .accounts({ pauser: admin.publicKey, protocol: protocolPda })
+      .rpc();
+
+    const [betPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("bet"), marketPda.toBuffer(), bettor1.publicKey.toBuffer()],
+      program.programId
+    );
+    const [reputationPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("reputation"), bettor1.publicKey.toBuffer()],
+      program.programId
+    );
+
+    try {
+      await program.methods
+        .placeBet(new anchor.BN(0.5 * LAMPORTS_PER_SOL), true, 0, new anchor.BN(0))
+        .accounts({
+          bettor: bettor1.publicKey,
+          market: marketPda,
+          bet: betPda,
+          vault: vaultPda,
+          reputation: reputationPda,
+          protocol: protocolPda,
+          collateralMint: null,
+          vaultToken: null,
+          bettorToken: null,
+          tokenProgram: null,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([bettor1])
+        .rpc();
+      expect.fail("Should have thrown");
+    } catch (err: any) {
+      expect(err.error.errorCode.code).to.equal("OperationPaused");
+    }
+
+    await program.methods
+      .setPaused(0)
+      .accounts({ pauser: admin.publicKey, protocol: protocolPda })
+      .rpc();
+  });
+
   it("Rejects bet below minimum", async () => {
     const tinyBettor = Keypair.generate();
     const sig = await provider.connection.requestAirdrop(
@@ -213,7 +393,7 @@ describe("clawbets", () => {
 
     try {
       await program.methods
-        .placeBet(new anchor.BN(1000), true)
+        .placeBet(new anchor.BN(1000), true, 0, new anchor.BN(0))
         .accounts({
           bettor: tinyBettor.publicKey,
           market: marketPda,
@@ -221,6 +401,10 @@ describe("clawbets", () => {
           vault: vaultPda,
           reputation: reputationPda,
           protocol: protocolPda,
+          collateralMint: null,
+          vaultToken: null,
+          bettorToken: null,
+          tokenProgram: null,
           systemProgram: SystemProgram.programId,
         })
         .signers([tinyBettor])
@@ -231,6 +415,76 @@ describe("clawbets", () => {
     }
   });
 
+  it("Withdraws a bet before the deadline, minus the exit penalty", async () => {
+    const leaver = Keypair.generate();
+    const sig = await provider.connection.requestAirdrop(
+      leaver.publicKey,
+      2 * LAMPORTS_PER_SOL
+    );
+    await provider.connection.confirmTransaction(sig);
+
+    const [betPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("bet"), marketPda.toBuffer(), leaver.publicKey.toBuffer()],
+      program.programId
+    );
+    const [reputationPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("reputation"), leaver.publicKey.toBuffer()],
+      program.programId
+    );
+
+    await program.methods
+      .placeBet(new anchor.BN(LAMPORTS_PER_SOL), true, 0, new anchor.BN(0))
+      .accounts({
+        bettor: leaver.publicKey,
+        market: marketPda,
+        bet: betPda,
+        vault: vaultPda,
+        reputation: reputationPda,
+        protocol: protocolPda,
+        collateralMint: null,
+        vaultToken: null,
+        bettorToken: null,
+        tokenProgram: null,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([leaver])
+      .rpc();
+
+    const vaultBefore = await provider.connection.getBalance(vaultPda);
+
+    await program.methods
+      .withdrawBet()
+      .accounts({
+        bettor: leaver.publicKey,
+        market: marketPda,
+        bet: betPda,
+        vault: vaultPda,
+        reputation: reputationPda,
+        protocol: protocolPda,
+        collateralMint: null,
+        vaultToken: null,
+        bettorToken: null,
+        tokenProgram: null,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([leaver])
+      .rpc();
+
+    // 5% exit penalty stays in the vault for the remaining bettors
+    const penalty = 0.05 * LAMPORTS_PER_SOL;
+    const vaultAfter = await provider.connection.getBalance(vaultPda);
+    expect(vaultBefore - vaultAfter).to.equal(LAMPORTS_PER_SOL - penalty);
+    expect(await provider.connection.getAccountInfo(betPda)).to.equal(null);
+
+    const market = await program.account.market.fetch(marketPda);
+    expect(market.totalYes.toNumber()).to.equal(1.5 * LAMPORTS_PER_SOL);
+    expect(market.yesCount).to.equal(1);
+    expect(market.penaltyPool.toNumber()).to.equal(penalty);
+
+    const rep = await program.account.agentReputation.fetch(reputationPda);
+    expect(rep.totalBets).to.equal(0);
+  });
+
   it("Fetches reputation", async () => {
     const [reputationPda] = PublicKey.findProgramAddressSync(
       [Buffer.from("reputation"), bettor1.publicKey.toBuffer()],
@@ -239,26 +493,29 @@ describe("clawbets", () => {
 
     const rep = await program.account.agentReputation.fetch(reputationPda);
     expect(rep.totalBets).to.equal(1);
-    expect(rep.totalWagered.toNumber()).to.equal(LAMPORTS_PER_SOL);
+    expect(rep.totalWagered.toNumber()).to.equal(1.5 * LAMPORTS_PER_SOL);
   });
 
-  it("Cannot cancel market with existing bets", async () => {
+  it("Only the creator or admin can cancel a market", async () => {
     try {
       await program.methods
-        .cancelMarket()
+        .cancelMarket({ invalidParameters: {} })
         .accounts({
-          creator: admin.publicKey,
+          authority: bettor1.publicKey,
           market: marketPda,
+          protocol: protocolPda,
         })
+        .signers([bettor1])
         .rpc();
       expect.fail("Should have thrown");
     } catch (err: any) {
-      expect(err.error.errorCode.code).to.equal("MarketHasBets");
+      expect(err.error.errorCode.code).to.equal("UnauthorizedCancel");
     }
   });
 
   it("Vault holds escrowed SOL", async () => {
     const vaultBalance = await provider.connection.getBalance(vaultPda);
-    expect(vaultBalance).to.equal(1.5 * LAMPORTS_PER_SOL);
+    // Open bets plus the exit penalty left by the withdrawn bet
+    expect(vaultBalance).to.equal(2.05 * LAMPORTS_PER_SOL);
   });
 });